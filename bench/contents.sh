@@ -0,0 +1,57 @@
+#!/bin/sh
+# Compares the throughput of revealing file contents between a baseline
+# revision and the working tree. By default, the baseline is the revision
+# before contents started to be copied by the kernel.
+#
+# usage: bench/contents.sh [REVISION [SIZE_IN_MIB [RUNS]]]
+
+set -e
+
+if [ -n "${1}" ]; then
+  revision=${1}
+else
+  revision=$(git log --format=%H -S copyContents --reverse -- src/revelio.c |
+    head -n 1)~1
+fi
+size=${2:-512}
+runs=${3:-3}
+directory=$(mktemp -d)
+trap 'rm -rf "${directory}"' EXIT INT TERM
+
+git show "${revision}:src/revelio.c" > "${directory}/baseline.c"
+${CC:-cc} -std=c99 -pedantic -Os -o "${directory}/baseline" \
+  "${directory}/baseline.c"
+${CC:-cc} -std=c99 -pedantic -Os -o "${directory}/current" src/revelio.c
+head -c $((size * 1048576)) /dev/urandom | base64 > "${directory}/input"
+input_size=$(wc -c < "${directory}/input")
+
+now() {
+  date +%s.%N
+}
+
+measure() {
+  binary=${1}
+  destination=${2}
+  total=0
+  run=0
+  while [ ${run} -lt ${runs} ]; do
+    start=$(now)
+    if [ "${destination}" = pipe ]; then
+      "${directory}/${binary}" "${directory}/input" | cat > /dev/null
+    else
+      "${directory}/${binary}" "${directory}/input" > "${directory}/output"
+    fi
+    end=$(now)
+    total=$(awk "BEGIN { print ${total} + ${end} - ${start} }")
+    run=$((run + 1))
+  done
+  awk "BEGIN { printf \"%.1f\", ${input_size} * ${runs} / 1048576 / ${total} }"
+}
+
+printf '%-10s %-6s %12s\n' binary target 'MiB/s'
+for destination in pipe file; do
+  for binary in baseline current; do
+    printf '%-10s %-6s %12s\n' ${binary} ${destination} \
+      "$(measure ${binary} ${destination})"
+  done
+done
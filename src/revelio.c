@@ -1,23 +1,58 @@
-#define _POSIX_C_SOURCE 200809L
+#define _GNU_SOURCE
+#include <ctype.h>
 #include <dirent.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <fnmatch.h>
 #include <grp.h>
+#include <locale.h>
 #include <pwd.h>
+#include <regex.h>
 #include <stdarg.h>
+#include <stdint.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
+#include <sys/sendfile.h>
 #include <sys/stat.h>
+#include <sys/sysmacros.h>
+#include <sys/xattr.h>
 #include <time.h>
 #include <unistd.h>
 
+#define PARSE_CONTENTS_MODE_OPTION(option, contentsMode)                       \
+  PARSE_OPTION(option, selectInfoType(InfoType_Contents);                      \
+               contentsMode_g = contentsMode; continue);
+#define PARSE_DATE_FORMAT_OPTION(option, dateFormat)                           \
+  PARSE_OPTION(option, dateFormat_g = dateFormat; continue);
+#define PARSE_DIRECTORY_MODE_OPTION(option, directoryMode)                     \
+  PARSE_OPTION(option, selectInfoType(InfoType_Contents);                      \
+               directoryMode_g = directoryMode; continue);
+#define PARSE_FILTER_OPTION(option, isRegex, isExcluding)                      \
+  PARSE_VALUE_OPTION(option,                                                   \
+                     addFilter(arguments[argumentIndex], isRegex, isExcluding));
 #define PARSE_INFO_TYPE_OPTION(option, infoType)                               \
-  PARSE_OPTION(option, infoType_g = infoType; continue);
+  PARSE_OPTION(option, selectInfoType(infoType); continue);
 #define PARSE_OPTION(option, action)                                           \
   if (!strcmp("-" option, arguments[argumentIndex])) {                         \
     action;                                                                    \
   }
+#define PARSE_OUTPUT_MODE_OPTION(option, outputMode)                           \
+  PARSE_OPTION(option, outputMode_g = outputMode; continue);
+#define PARSE_QUOTING_MODE_OPTION(option, quotingMode)                         \
+  PARSE_OPTION(option, quotingMode_g = quotingMode; continue);
+#define PARSE_SORT_MODE_OPTION(option, sortMode)                               \
+  PARSE_OPTION(option, sortMode_g = sortMode; continue);
+#define PARSE_TYPE_FILTER_OPTION(option, typeFilter)                           \
+  PARSE_OPTION(option, typeFilter_g = typeFilter; continue);
 #define PARSE_SYMLINK_OPTION(option, isFollowingSymlinks)                      \
   PARSE_OPTION(option, isFollowingSymlinks_g = isFollowingSymlinks; continue);
+#define PARSE_VALUE_OPTION(option, action)                                     \
+  PARSE_OPTION(                                                                \
+      option, if (++argumentIndex == totalOfArguments) {                       \
+        die("option \"-" option "\" requires a value.\n");                    \
+      } action;                                                                \
+      continue);
 
 enum InfoType {
   InfoType_Contents,
@@ -30,25 +65,411 @@ enum InfoType {
   InfoType_UID,
   InfoType_Group,
   InfoType_GID,
-  InfoType_ModifiedDate
+  InfoType_ModifiedDate,
+  InfoType_AccessDate,
+  InfoType_StatusChangeDate,
+  InfoType_BirthDate,
+  InfoType_Inode,
+  InfoType_HardLinks,
+  InfoType_Device,
+  InfoType_DeviceNumbers,
+  InfoType_Blocks,
+  InfoType_AllocatedSize,
+  InfoType_Mode,
+  InfoType_HumanAllocatedSize,
+  InfoType_TotalSize,
+  InfoType_HumanTotalSize,
+  InfoType_TotalAllocatedSize,
+  InfoType_HumanTotalAllocatedSize,
+  InfoType_Resolution,
+  InfoType_Format,
+  InfoType_MIMEType,
+  InfoType_SHA256,
+  InfoType_SHA1,
+  InfoType_MD5,
+  InfoType_XXH64,
+  InfoType_Attributes,
+  InfoType_Attribute
 };
 
+enum ContentsMode {
+  ContentsMode_Raw,
+  ContentsMode_ForcedRaw,
+  ContentsMode_Hexdump,
+  ContentsMode_Escaped,
+  ContentsMode_Caret
+};
+
+enum ContentsRange {
+  ContentsRange_All,
+  ContentsRange_FirstLines,
+  ContentsRange_LastLines,
+  ContentsRange_Lines,
+  ContentsRange_Bytes
+};
+
+enum DateFormat {
+  DateFormat_Default,
+  DateFormat_ISO,
+  DateFormat_Epoch,
+  DateFormat_Relative,
+  DateFormat_Custom
+};
+
+enum DirectoryMode {
+  DirectoryMode_List,
+  DirectoryMode_Tree,
+  DirectoryMode_ASCIITree,
+  DirectoryMode_Long,
+  DirectoryMode_Usage
+};
+
+enum SortMode {
+  SortMode_Name,
+  SortMode_Natural,
+  SortMode_Locale,
+  SortMode_CaseInsensitive,
+  SortMode_Size,
+  SortMode_ModifiedDate,
+  SortMode_Type,
+  SortMode_Extension,
+  SortMode_None
+};
+
+enum TypeFilter {
+  TypeFilter_All,
+  TypeFilter_Directories,
+  TypeFilter_RegularFiles,
+  TypeFilter_Executables
+};
+
+enum QuotingMode {
+  QuotingMode_None,
+  QuotingMode_Shell,
+  QuotingMode_C
+};
+
+enum OutputMode {
+  OutputMode_Plain,
+  OutputMode_JSON,
+  OutputMode_Format,
+  OutputMode_Manifest
+};
+
+struct Child {
+  char *name;
+  int error;
+  int isShown;
+  struct stat metadata;
+};
+
+struct Digest {
+  int infoType;
+  uint32_t state[8];
+  uint64_t lanes[4];
+  unsigned char block[64];
+  size_t blockLength;
+  uint64_t totalOfBytes;
+};
+
+struct Entry {
+  char *path;
+  char *name;
+  int depth;
+  int isLast;
+  struct stat metadata;
+  struct Entry *parent;
+};
+
+struct Filter {
+  char *pattern;
+  int isRegex;
+  int isExcluding;
+  regex_t expression;
+};
+
+struct IgnoreRule {
+  char *pattern;
+  size_t pathLength;
+  int isNegated;
+  int isDirectoryOnly;
+  int isAnchored;
+};
+
+struct Inode {
+  dev_t device;
+  ino_t inode;
+};
+
+struct Signature {
+  size_t offset;
+  char *bytes;
+  size_t length;
+  char *format;
+  char *mimeType;
+};
+
+struct Text {
+  char *characters;
+  size_t length;
+  size_t capacity;
+};
+
+struct Usage {
+  char *name;
+  unsigned long long size;
+  unsigned long long allocatedSize;
+};
+
+static void addFilter(char *pattern, int isRegex, int isExcluding);
 static void *allocate(size_t bytes);
+static void appendText(struct Text *text, char *format, va_list arguments);
+static void beginDigest(struct Digest *digest, int infoType);
+static void beginRecord(char *path, int infoType);
+static void clearTotals(void);
+static int compareNaturally(char *string, char *otherString);
+static int copyContents(int descriptor, off_t offset, long remainingBytes,
+                        char *path);
+static int countInode(struct stat *metadata);
+static unsigned long long decodeInteger(unsigned char *bytes, size_t size,
+                                        int isBigEndian);
+static char *detectFormat(unsigned char *bytes, size_t totalOfBytes,
+                          struct Text *format);
 static void die(char *format, ...);
+static void endRecord(void);
+static int fail(int error, char *format, ...);
+static char *findExtension(char *name);
+static off_t findRangeStart(FILE *stream);
+static void finishDigest(struct Digest *digest, struct Text *value);
+static void formatAttributeNames(struct Text *text, struct Text *names,
+                                 int isSplit);
+static void formatDate(struct Text *text, char *format,
+                       struct tm *calendarDate);
+static void formatEscapedPath(struct Text *text, char *path);
+static void formatInfos(struct Entry *entry, struct Text *line,
+                        char *separator);
+static void formatPath(struct Text *text, char *path);
+static void formatText(struct Text *text, char *format, ...);
+static void formatTreePrefix(struct Text *line, struct Entry *directory,
+                             char **connectors);
+static void hashBlock(struct Digest *digest, unsigned char *block);
+static void hashMD5Block(uint32_t *state, unsigned char *block);
+static void hashSHA1Block(uint32_t *state, unsigned char *block);
+static void hashSHA256Block(uint32_t *state, unsigned char *block);
+static void hashXXH64Stripe(uint64_t *lanes, unsigned char *stripe);
+static int isBinary(unsigned char *bytes, size_t totalOfBytes);
+static int isIgnored(char *path, struct stat *metadata);
+static int isMatching(struct Filter *filter, char *name);
+static int isNumeric(int infoType);
+static int isPruned(struct Child *child, char *path);
+static int isRevealing(int infoType);
+static int isSelected(struct Child *child);
+static int isUTF8(unsigned char *bytes, size_t totalOfBytes);
+static void loadIgnoreFile(int directoryDescriptor, char *directoryPath,
+                           char *name, size_t pathLength);
+static long parseNumber(char *string, long minimum);
+static void parseRange(char *string, int contentsRange);
+static void printBase64(unsigned char *bytes, size_t totalOfBytes);
+static size_t printEscaped(unsigned char *bytes, size_t totalOfBytes,
+                           int isEnd);
+static void printFailure(char *path, int infoType);
+static void printFailures(char *path);
+static void printHexdump(unsigned char *bytes, size_t totalOfBytes,
+                         unsigned long long offset);
+static void printJSONString(char *string);
+static void printPlaceholders(void);
+static void printRows(size_t totalOfColumns, char *alignments);
+static void printUsages(struct Entry *directory);
+static void printValue(char *path, int infoType, struct Text *value);
+static ssize_t readAttribute(char *path, int infoType, char *buffer,
+                             size_t size);
+static int readChild(int descriptor, char *path, int isStating,
+                     int isRecursive, void (*visit)(struct Entry *entry),
+                     struct Child *child);
+static size_t readRange(FILE *stream, unsigned char *bytes, size_t size,
+                        long *remainingBytes, long *remainingLines);
+static void *reallocate(void *allocation, size_t bytes);
 static void reveal(char *path);
-static void revealDirectory(char *path);
-static void revealFile(char *path);
-static void revealGroup(char *path, struct stat *metadata);
-static void revealHumanSize(struct stat *metadata);
-static void revealModifiedDate(struct stat *metadata);
-static void revealPermissions(struct stat *metadata);
-static void revealSymlink(char *path);
-static void revealType(struct stat *metadata);
-static void revealUser(char *path, struct stat *metadata);
-static int sortAlphabetically(const void *stringI, const void *stringII);
-
-static int infoType_g = InfoType_Contents;
+static int revealAttribute(char *path, int infoType, struct Text *value);
+static int revealBirthDate(char *path, struct Text *value);
+static int revealContents(struct Entry *entry);
+static void revealDate(struct timespec *date, struct Text *value);
+static int revealDigest(char *path, struct stat *metadata, int infoType,
+                        struct Text *value);
+static int revealDirectory(struct Entry *directory);
+static void revealEntry(struct Entry *entry);
+static int revealFile(char *path);
+static int revealFileFormat(char *path, struct stat *metadata, int infoType,
+                            struct Text *value);
+static void revealFormat(struct Entry *entry);
+static int revealGroup(char *path, struct stat *metadata, struct Text *value);
+static void revealHumanSize(unsigned long long size, struct Text *value);
+static int revealInfo(char *path, struct stat *metadata, int infoType,
+                      struct Text *value);
+static void revealManifestEntry(struct Entry *entry);
+static void revealMode(struct stat *metadata, struct Text *value);
+static void revealName(struct Entry *entry);
+static void revealPaths(char *listPath);
+static void revealPermissions(struct stat *metadata, struct Text *value);
+static int revealResolution(char *path, struct Text *value);
+static void revealRow(struct Entry *entry);
+static int revealSymlink(char *path, struct stat *metadata,
+                         struct Text *value);
+static void revealTotalSize(char *path, struct stat *metadata, int infoType,
+                            struct Text *value);
+static void revealTreeNode(struct Entry *entry);
+static void revealType(struct stat *metadata, struct Text *value);
+static void revealUsage(struct Entry *entry);
+static int revealUser(char *path, struct stat *metadata, struct Text *value);
+static uint32_t rotateLeft(uint32_t value, int shift);
+static uint64_t rotateLeft64(uint64_t value, int shift);
+static void selectInfoType(int infoType);
+static int sortChildren(const void *childI, const void *childII);
+static int sortUsagesBySize(const void *usageI, const void *usageII);
+static void sumDirectory(struct Entry *directory);
+static void sumSize(struct Entry *entry);
+static void updateDigest(struct Digest *digest, unsigned char *bytes,
+                         size_t totalOfBytes);
+static void verifyManifest(char *manifestPath);
+static void walkDirectory(int descriptor, struct Entry *directory,
+                          int isRecursive, void (*visit)(struct Entry *entry));
+static int walkSubdirectory(struct Entry *directory, int isRecursive,
+                            void (*visit)(struct Entry *entry));
+
+static char *attributeName_g = NULL;
+static char **cells_g = NULL;
+static char *datePattern_g = NULL;
+static char *format_g = NULL;
+static struct Filter *filters_g = NULL;
+static struct IgnoreRule *ignoreRules_g = NULL;
+static struct Inode *countedInodes_g = NULL;
+static struct Usage *usages_g = NULL;
+static char *infoTypeNames_g[] = {
+    "contents", "type", "size", "human-size", "permissions",
+    "octal-permissions", "user", "uid", "group", "gid", "modified-date",
+    "access-date", "status-change-date", "birth-date", "inode", "hard-links",
+    "device", "device-numbers", "blocks", "allocated-size",
+    "mode", "human-allocated-size", "total-size", "human-total-size",
+    "total-allocated-size", "human-total-allocated-size", "resolution",
+    "format", "mime-type", "sha256", "sha1", "md5", "xxh64", "attributes",
+    "attribute"};
+static struct Signature signatures_g[] = {
+    {0, "\xef\xbb\xbf", 3, "UTF-8 text", "text/plain"},
+    {0, "\xff\xfe", 2, "UTF-16LE text", "text/plain"},
+    {0, "\xfe\xff", 2, "UTF-16BE text", "text/plain"},
+    {0, "\xfe\xed\xfa\xce", 4, "Mach-O", "application/x-mach-binary"},
+    {0, "\xfe\xed\xfa\xcf", 4, "Mach-O", "application/x-mach-binary"},
+    {0, "\xce\xfa\xed\xfe", 4, "Mach-O", "application/x-mach-binary"},
+    {0, "\xcf\xfa\xed\xfe", 4, "Mach-O", "application/x-mach-binary"},
+    {0, "\0asm", 4, "WebAssembly", "application/wasm"},
+    {0, "!<arch>\n", 8, "ar archive", "application/x-archive"},
+    {0, "\x1f\x8b", 2, "gzip", "application/gzip"},
+    {0, "\x28\xb5\x2f\xfd", 4, "zstd", "application/zstd"},
+    {0, "\xfd" "7zXZ\0", 6, "xz", "application/x-xz"},
+    {0, "BZh", 3, "bzip2", "application/x-bzip2"},
+    {0, "\x1f\x9d", 2, "compress", "application/x-compress"},
+    {0, "\x04\x22\x4d\x18", 4, "lz4", "application/x-lz4"},
+    {0, "PK\x03\x04", 4, "zip", "application/zip"},
+    {0, "PK\x05\x06", 4, "zip", "application/zip"},
+    {0, "7z\xbc\xaf\x27\x1c", 6, "7-zip", "application/x-7z-compressed"},
+    {0, "\xed\xab\xee\xdb", 4, "RPM", "application/x-rpm"},
+    {257, "ustar", 5, "tar", "application/x-tar"},
+    {0, "\x89PNG\r\n\x1a\n", 8, "PNG", "image/png"},
+    {0, "\xff\xd8\xff", 3, "JPEG", "image/jpeg"},
+    {0, "GIF87a", 6, "GIF", "image/gif"},
+    {0, "GIF89a", 6, "GIF", "image/gif"},
+    {8, "WEBP", 4, "WebP", "image/webp"},
+    {0, "%PDF-", 5, "PDF", "application/pdf"},
+    {0, "SQLite format 3\0", 16, "SQLite", "application/vnd.sqlite3"},
+    {0, "OggS", 4, "Ogg", "audio/ogg"},
+    {0, "fLaC", 4, "FLAC", "audio/flac"},
+    {0, "ID3", 3, "MP3", "audio/mpeg"}};
+static uint32_t md5Constants_g[] = {
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a,
+    0xa8304613, 0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be,
+    0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340,
+    0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8,
+    0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c,
+    0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+    0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92,
+    0xffeff47d, 0x85845dd1, 0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1,
+    0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391};
+static uint32_t sha256Constants_g[] = {
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1,
+    0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3,
+    0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147,
+    0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+    0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208,
+    0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2};
+static uint64_t xxh64Primes_g[] = {
+    0x9e3779b185ebca87, 0xc2b2ae3d27d4eb4f, 0x165667b19e3779f9,
+    0x85ebca77c2b2ae63, 0x27d4eb2f165667c5};
+static unsigned long long infoTypes_g = 1 << InfoType_Contents;
+static int contentsMode_g = ContentsMode_Raw;
+static int contentsRange_g = ContentsRange_All;
+static int dateFormat_g = DateFormat_Default;
+static int directoryMode_g = DirectoryMode_List;
+static int hasFailed_g = 0;
+static int hasListedEntry_g = 0;
+static long humanSizePrecision_g = 1;
+static int isCrossingFileSystems_g = 1;
 static int isFollowingSymlinks_g = 0;
+static int isHidingDotEntries_g = 0;
+static int isRespectingIgnoreFiles_g = 0;
+static int isRecursive_g = 0;
+static int isReversingSort_g = 0;
+static int isResettingFilters_g = 1;
+static int isResettingInfoTypes_g = 1;
+static int isRevealingNanoseconds_g = 0;
+static int isStrict_g = 0;
+static int isUsingBinaryUnits_g = 0;
+static int isUsingUTC_g = 0;
+static long maxDepth_g = -1;
+static long rangeLength_g = 0;
+static long rangeStart_g = 0;
+static int outputMode_g = OutputMode_Plain;
+static int quotingMode_g = QuotingMode_None;
+static int sortMode_g = SortMode_Name;
+static int typeFilter_g = TypeFilter_All;
+static char inputDelimiter_g = '\n';
+static char terminator_g = '\n';
+static size_t countedInodesCapacity_g = 0;
+static size_t totalOfCells_g = 0;
+static size_t totalOfCountedInodes_g = 0;
+static size_t totalOfFilters_g = 0;
+static size_t totalOfIgnoreRules_g = 0;
+static size_t totalOfUsages_g = 0;
+static unsigned long long totalAllocatedSize_g = 0;
+static unsigned long long totalSize_g = 0;
+static struct Text error_g = {NULL, 0, 0};
+
+static void addFilter(char *pattern, int isRegex, int isExcluding) {
+  if (isResettingFilters_g) {
+    for (; totalOfFilters_g; totalOfFilters_g--) {
+      if (filters_g[totalOfFilters_g - 1].isRegex) {
+        regfree(&filters_g[totalOfFilters_g - 1].expression);
+      }
+    }
+    isResettingFilters_g = 0;
+  }
+  filters_g =
+      reallocate(filters_g, sizeof(struct Filter) * (totalOfFilters_g + 1));
+  filters_g[totalOfFilters_g].pattern = pattern;
+  filters_g[totalOfFilters_g].isRegex = isRegex;
+  filters_g[totalOfFilters_g].isExcluding = isExcluding;
+  if (isRegex && regcomp(&filters_g[totalOfFilters_g].expression, pattern,
+                         REG_EXTENDED | REG_NOSUB)) {
+    die("invalid regex \"%s\".\n", pattern);
+  }
+  totalOfFilters_g++;
+}
 
 static void *allocate(size_t bytes) {
   void *allocation;
@@ -58,6 +479,325 @@ static void *allocate(size_t bytes) {
   return allocation;
 }
 
+static void appendText(struct Text *text, char *format, va_list arguments) {
+  va_list argumentsCopy;
+  int length;
+  va_copy(argumentsCopy, arguments);
+  length = vsnprintf(NULL, 0, format, argumentsCopy);
+  va_end(argumentsCopy);
+  if (text->length + length + 1 > text->capacity) {
+    text->capacity = (text->length + length + 1) * 2;
+    text->characters = reallocate(text->characters, text->capacity);
+  }
+  vsnprintf(text->characters + text->length, length + 1, format, arguments);
+  text->length += length;
+}
+
+static void beginDigest(struct Digest *digest, int infoType) {
+  uint32_t md5State[] = {0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476};
+  uint32_t sha1State[] = {0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476,
+                          0xc3d2e1f0};
+  uint32_t sha256State[] = {0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+                            0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19};
+  digest->infoType = infoType;
+  digest->blockLength = 0;
+  digest->totalOfBytes = 0;
+  if (infoType == InfoType_SHA256) {
+    memcpy(digest->state, sha256State, sizeof(sha256State));
+  } else if (infoType == InfoType_SHA1) {
+    memcpy(digest->state, sha1State, sizeof(sha1State));
+  } else if (infoType == InfoType_MD5) {
+    memcpy(digest->state, md5State, sizeof(md5State));
+  } else {
+    digest->lanes[0] = xxh64Primes_g[0] + xxh64Primes_g[1];
+    digest->lanes[1] = xxh64Primes_g[1];
+    digest->lanes[2] = 0;
+    digest->lanes[3] = -xxh64Primes_g[0];
+  }
+}
+
+static void beginRecord(char *path, int infoType) {
+  if (outputMode_g == OutputMode_JSON) {
+    printf("{\"path\":");
+    printJSONString(path);
+    printf(",\"info\":\"%s\",\"value\":", infoTypeNames_g[infoType]);
+  }
+}
+
+static void clearTotals(void) {
+  totalSize_g = 0;
+  totalAllocatedSize_g = 0;
+  if (countedInodes_g) {
+    memset(countedInodes_g, 0, sizeof(struct Inode) * countedInodesCapacity_g);
+  }
+  totalOfCountedInodes_g = 0;
+}
+
+static int compareNaturally(char *string, char *otherString) {
+  size_t length;
+  size_t otherLength;
+  int order;
+  while (*string && *otherString) {
+    if (isdigit((unsigned char)*string) &&
+        isdigit((unsigned char)*otherString)) {
+      for (; *string == '0'; string++)
+        ;
+      for (; *otherString == '0'; otherString++)
+        ;
+      for (length = 0; isdigit((unsigned char)string[length]); length++)
+        ;
+      for (otherLength = 0; isdigit((unsigned char)otherString[otherLength]);
+           otherLength++)
+        ;
+      if (length != otherLength) {
+        return length < otherLength ? -1 : 1;
+      } else if ((order = strncmp(string, otherString, length))) {
+        return order;
+      }
+      string += length;
+      otherString += otherLength;
+    } else if (*string != *otherString) {
+      return (unsigned char)*string - (unsigned char)*otherString;
+    } else {
+      string++;
+      otherString++;
+    }
+  }
+  return (unsigned char)*string - (unsigned char)*otherString;
+}
+
+static int copyContents(int descriptor, off_t offset, long remainingBytes,
+                        char *path) {
+  char *buffer;
+  loff_t position;
+  size_t size;
+  ssize_t length;
+  ssize_t totalOfWrittenBytes;
+  ssize_t writtenBytes;
+  struct stat metadata;
+  if (fflush(stdout) || fstat(STDOUT_FILENO, &metadata)) {
+    return fail(errno, "can't write contents of \"%s\"", path);
+  }
+  while (remainingBytes) {
+    size = remainingBytes >= 0 && remainingBytes < 1 << 30 ? remainingBytes
+                                                           : 1 << 30;
+    position = offset;
+    if (S_ISREG(metadata.st_mode)) {
+      length = copy_file_range(descriptor, &position, STDOUT_FILENO, NULL, size,
+                               0);
+    } else if (S_ISFIFO(metadata.st_mode)) {
+      length = splice(descriptor, &position, STDOUT_FILENO, NULL, size,
+                      SPLICE_F_MOVE);
+    } else {
+      length = sendfile(STDOUT_FILENO, descriptor, &offset, size);
+      position = offset;
+    }
+    if (length < 0 && errno == EINTR) {
+      continue;
+    } else if (length < 0 &&
+               (errno == EINVAL || errno == ENOSYS || errno == EXDEV ||
+                errno == EOPNOTSUPP || errno == EBADF)) {
+      break;
+    } else if (length < 0 && errno == EPIPE) {
+      exit(1);
+    } else if (length < 0) {
+      return fail(errno, "can't copy contents of \"%s\"", path);
+    } else if (!length) {
+      return 0;
+    }
+    offset = position;
+    remainingBytes -= remainingBytes >= 0 ? length : 0;
+  }
+  buffer = allocate(131072);
+  while (remainingBytes) {
+    size = remainingBytes >= 0 && remainingBytes < 131072 ? remainingBytes
+                                                          : 131072;
+    if ((length = pread(descriptor, buffer, size, offset)) < 0 &&
+        errno == EINTR) {
+      continue;
+    } else if (length < 0) {
+      free(buffer);
+      return fail(errno, "can't read file \"%s\"", path);
+    } else if (!length) {
+      break;
+    }
+    for (totalOfWrittenBytes = 0; totalOfWrittenBytes < length;) {
+      writtenBytes = write(STDOUT_FILENO, buffer + totalOfWrittenBytes,
+                           length - totalOfWrittenBytes);
+      if (writtenBytes < 0 && errno == EPIPE) {
+        exit(1);
+      } else if (writtenBytes < 0 && errno != EINTR) {
+        free(buffer);
+        return fail(errno, "can't write contents of \"%s\"", path);
+      }
+      totalOfWrittenBytes += writtenBytes > 0 ? writtenBytes : 0;
+    }
+    offset += length;
+    remainingBytes -= remainingBytes >= 0 ? length : 0;
+  }
+  free(buffer);
+  return 0;
+}
+
+static int countInode(struct stat *metadata) {
+  size_t index;
+  size_t oldCapacity = countedInodesCapacity_g;
+  struct Inode *oldInodes = countedInodes_g;
+  struct stat oldMetadata;
+  if ((totalOfCountedInodes_g + 1) * 2 > countedInodesCapacity_g) {
+    countedInodesCapacity_g = oldCapacity ? oldCapacity * 2 : 64;
+    countedInodes_g = allocate(sizeof(struct Inode) * countedInodesCapacity_g);
+    memset(countedInodes_g, 0, sizeof(struct Inode) * countedInodesCapacity_g);
+    totalOfCountedInodes_g = 0;
+    for (index = 0; index < oldCapacity; index++) {
+      if (oldInodes[index].inode) {
+        oldMetadata.st_dev = oldInodes[index].device;
+        oldMetadata.st_ino = oldInodes[index].inode;
+        countInode(&oldMetadata);
+      }
+    }
+    free(oldInodes);
+  }
+  for (index = ((unsigned long long)metadata->st_ino * 0x9e3779b97f4a7c15ULL ^
+                (unsigned long long)metadata->st_dev) %
+               countedInodesCapacity_g;
+       countedInodes_g[index].inode;
+       index = (index + 1) % countedInodesCapacity_g) {
+    if (countedInodes_g[index].device == metadata->st_dev &&
+        countedInodes_g[index].inode == metadata->st_ino) {
+      return 0;
+    }
+  }
+  countedInodes_g[index].device = metadata->st_dev;
+  countedInodes_g[index].inode = metadata->st_ino;
+  totalOfCountedInodes_g++;
+  return 1;
+}
+
+static unsigned long long decodeInteger(unsigned char *bytes, size_t size,
+                                        int isBigEndian) {
+  size_t byteIndex;
+  unsigned long long integer = 0;
+  for (byteIndex = 0; byteIndex < size; byteIndex++) {
+    integer = integer << 8 |
+              bytes[isBigEndian ? byteIndex : size - 1 - byteIndex];
+  }
+  return integer;
+}
+
+static char *detectFormat(unsigned char *bytes, size_t totalOfBytes,
+                          struct Text *format) {
+  char *interpreter;
+  char *shellInterpreters[] = {"sh", "bash", "dash", "ksh", "zsh"};
+  int hasInterpreter = 0;
+  int is64Bit = totalOfBytes > 4 && bytes[4] == 2;
+  int isBigEndian = totalOfBytes > 5 && bytes[5] == 2;
+  size_t byteIndex;
+  size_t headerIndex;
+  size_t interpreterIndex;
+  size_t length;
+  size_t signatureIndex;
+  unsigned long long headerOffset;
+  unsigned long long headerSize;
+  unsigned long long type;
+  if (!totalOfBytes) {
+    formatText(format, "empty");
+    return "inode/x-empty";
+  } else if (totalOfBytes >= 64 && !memcmp(bytes, "\x7f" "ELF", 4)) {
+    type = decodeInteger(bytes + 16, 2, isBigEndian);
+    headerOffset = decodeInteger(bytes + (is64Bit ? 32 : 28), is64Bit ? 8 : 4,
+                                 isBigEndian);
+    headerSize = decodeInteger(bytes + (is64Bit ? 54 : 42), 2, isBigEndian);
+    for (headerIndex = 0;
+         type == 3 &&
+         headerIndex < decodeInteger(bytes + (is64Bit ? 56 : 44), 2,
+                                     isBigEndian) &&
+         headerOffset < totalOfBytes && totalOfBytes - headerOffset >= 4;
+         headerIndex++, headerOffset += headerSize) {
+      hasInterpreter |=
+          decodeInteger(bytes + headerOffset, 4, isBigEndian) == 3;
+    }
+    formatText(format, "ELF %s-bit %s %s", is64Bit ? "64" : "32",
+               isBigEndian ? "MSB" : "LSB",
+               type == 1                     ? "relocatable"
+               : type == 2                   ? "executable"
+               : type == 3 && hasInterpreter ? "pie executable"
+               : type == 3                   ? "shared object"
+               : type == 4                   ? "core dump"
+                                             : "file");
+    return type == 1                     ? "application/x-object"
+           : type == 3 && hasInterpreter ? "application/x-pie-executable"
+           : type == 3                   ? "application/x-sharedlib"
+           : type == 4                   ? "application/x-coredump"
+                                         : "application/x-executable";
+  } else if (totalOfBytes >= 64 && !memcmp(bytes, "MZ", 2)) {
+    headerOffset = decodeInteger(bytes + 60, 4, 0);
+    if (headerOffset + 26 > totalOfBytes ||
+        memcmp(bytes + headerOffset, "PE\0\0", 4)) {
+      formatText(format, "MS-DOS executable");
+      return "application/x-dosexec";
+    }
+    formatText(format, "%s %s",
+               decodeInteger(bytes + headerOffset + 24, 2, 0) == 0x20b
+                   ? "PE32+"
+                   : "PE32",
+               decodeInteger(bytes + headerOffset + 22, 2, 0) & 0x2000
+                   ? "DLL"
+                   : "executable");
+    return "application/vnd.microsoft.portable-executable";
+  } else if (totalOfBytes > 2 && !memcmp(bytes, "#!", 2)) {
+    interpreter = (char *)bytes + 2 + strspn((char *)bytes + 2, " \t");
+    length = strcspn(interpreter, " \t\r\n");
+    if (length >= 4 && !strncmp(interpreter + length - 4, "/env", 4)) {
+      interpreter += length + strspn(interpreter + length, " \t");
+      for (; *interpreter == '-'; interpreter += strspn(interpreter, " \t")) {
+        interpreter += strcspn(interpreter, " \t\r\n");
+      }
+      length = strcspn(interpreter, " \t\r\n");
+    }
+    for (byteIndex = length; byteIndex && interpreter[byteIndex - 1] != '/';
+         byteIndex--)
+      ;
+    interpreter += byteIndex;
+    length -= byteIndex;
+    formatText(format, "script (%.*s)", (int)length, interpreter);
+    for (interpreterIndex = 0; interpreterIndex < 5; interpreterIndex++) {
+      if (length == strlen(shellInterpreters[interpreterIndex]) &&
+          !strncmp(interpreter, shellInterpreters[interpreterIndex], length)) {
+        return "text/x-shellscript";
+      }
+    }
+    return !strncmp(interpreter, "python", 6) ? "text/x-script.python"
+           : !strncmp(interpreter, "perl", 4) ? "text/x-perl"
+           : !strncmp(interpreter, "ruby", 4) ? "text/x-ruby"
+           : !strncmp(interpreter, "node", 4) ? "application/javascript"
+                                              : "text/plain";
+  }
+  for (signatureIndex = 0;
+       signatureIndex < sizeof(signatures_g) / sizeof(struct Signature);
+       signatureIndex++) {
+    if (signatures_g[signatureIndex].offset +
+                signatures_g[signatureIndex].length <=
+            totalOfBytes &&
+        !memcmp(bytes + signatures_g[signatureIndex].offset,
+                signatures_g[signatureIndex].bytes,
+                signatures_g[signatureIndex].length)) {
+      formatText(format, "%s", signatures_g[signatureIndex].format);
+      return signatures_g[signatureIndex].mimeType;
+    }
+  }
+  if (!isBinary(bytes, totalOfBytes)) {
+    for (byteIndex = 0; byteIndex < totalOfBytes && bytes[byteIndex] < 128;
+         byteIndex++)
+      ;
+    formatText(format,
+               byteIndex == totalOfBytes ? "ASCII text" : "UTF-8 text");
+    return "text/plain";
+  }
+  formatText(format, "data");
+  return "application/octet-stream";
+}
+
 static void die(char *format, ...) {
   va_list arguments;
   va_start(arguments, format);
@@ -67,170 +807,2150 @@ static void die(char *format, ...) {
   exit(1);
 }
 
-static void reveal(char *path) {
-  struct stat metadata;
-  if (isFollowingSymlinks_g ? stat(path, &metadata) : lstat(path, &metadata)) {
-    die("can't stat \"%s\".\n", path);
-  } else if (infoType_g == InfoType_Contents && S_ISREG(metadata.st_mode)) {
-    revealFile(path);
-  } else if (infoType_g == InfoType_Contents && S_ISDIR(metadata.st_mode)) {
-    revealDirectory(path);
-  } else if (infoType_g == InfoType_Contents && S_ISLNK(metadata.st_mode)) {
-    revealSymlink(path);
-  } else if (infoType_g == InfoType_Contents) {
-    die("can't reveal contents of \"%s\".\n", path);
-  } else if (infoType_g == InfoType_Type) {
-    revealType(&metadata);
-  } else if (infoType_g == InfoType_Size) {
-    printf("%ld\n", metadata.st_size);
-  } else if (infoType_g == InfoType_HumanSize) {
-    revealHumanSize(&metadata);
-  } else if (infoType_g == InfoType_Permissions) {
-    revealPermissions(&metadata);
-  } else if (infoType_g == InfoType_OctalPermissions) {
-    printf("%o\n",
-           metadata.st_mode & (S_IRUSR | S_IWUSR | S_IXUSR | S_IRGRP | S_IWGRP |
-                               S_IXGRP | S_IROTH | S_IWOTH | S_IXOTH));
-  } else if (infoType_g == InfoType_User) {
-    revealUser(path, &metadata);
-  } else if (infoType_g == InfoType_UID) {
-    printf("%u\n", metadata.st_uid);
-  } else if (infoType_g == InfoType_Group) {
-    revealGroup(path, &metadata);
-  } else if (infoType_g == InfoType_GID) {
-    printf("%u\n", metadata.st_gid);
-  } else if (infoType_g == InfoType_ModifiedDate) {
-    revealModifiedDate(&metadata);
-  }
-}
-
-static void revealDirectory(char *path) {
-  DIR *stream = opendir(path);
-  char **entryNames;
-  char *entryName;
-  int entryIndexI;
-  int entryIndexII;
-  struct dirent *entry;
-  if (!stream) {
-    die("can't open directory \"%s\".\n", path);
+static void endRecord(void) {
+  if (outputMode_g == OutputMode_JSON) {
+    printf(",\"error\":null}%c", terminator_g);
   }
-  for (entryIndexI = -2; readdir(stream); entryIndexI++)
-    ;
-  if (!entryIndexI) {
-    goto close;
-  }
-  entryNames = allocate(sizeof(NULL) * entryIndexI);
-  entryIndexI = 0;
-  rewinddir(stream);
-  while ((entry = readdir(stream))) {
-    if (!strcmp(entry->d_name, ".") || !strcmp(entry->d_name, "..")) {
+}
+
+static int fail(int error, char *format, ...) {
+  va_list arguments;
+  error_g.length = 0;
+  va_start(arguments, format);
+  appendText(&error_g, format, arguments);
+  va_end(arguments);
+  if (error) {
+    formatText(&error_g, ": %s", strerror(error));
+  }
+  formatText(&error_g, ".");
+  fprintf(stderr, "revelio: %s\n", error_g.characters);
+  hasFailed_g = 1;
+  return -1;
+}
+
+static char *findExtension(char *name) {
+  char *extension = strrchr(name, '.');
+  return extension && extension != name ? extension + 1 : "";
+}
+
+static off_t findRangeStart(FILE *stream) {
+  long remainingLines = contentsRange_g == ContentsRange_Lines
+                            ? rangeStart_g - 1
+                            : rangeLength_g;
+  off_t end;
+  off_t offset = 0;
+  size_t byteIndex;
+  size_t totalOfBytes;
+  unsigned char bytes[65536];
+  if (contentsRange_g == ContentsRange_Bytes) {
+    return rangeStart_g;
+  } else if (contentsRange_g == ContentsRange_Lines) {
+    while (remainingLines &&
+           (totalOfBytes = fread(bytes, 1, sizeof(bytes), stream))) {
+      for (byteIndex = 0; byteIndex < totalOfBytes && remainingLines;
+           byteIndex++) {
+        remainingLines -= bytes[byteIndex] == '\n';
+      }
+      offset += byteIndex;
+    }
+    return ferror(stream) ? -1 : offset;
+  } else if (contentsRange_g != ContentsRange_LastLines) {
+    return 0;
+  } else if (fseeko(stream, 0, SEEK_END) || (end = ftello(stream)) < 0) {
+    return -1;
+  } else if (!remainingLines) {
+    return end;
+  }
+  for (offset = end; offset > 0;) {
+    totalOfBytes =
+        offset < (off_t)sizeof(bytes) ? (size_t)offset : sizeof(bytes);
+    offset -= totalOfBytes;
+    if (fseeko(stream, offset, SEEK_SET) ||
+        fread(bytes, 1, totalOfBytes, stream) != totalOfBytes) {
+      return -1;
+    }
+    for (byteIndex = totalOfBytes; byteIndex > 0; byteIndex--) {
+      if (bytes[byteIndex - 1] == '\n' &&
+          offset + (off_t)byteIndex != end && !--remainingLines) {
+        return offset + byteIndex;
+      }
+    }
+  }
+  return 0;
+}
+
+static void finishDigest(struct Digest *digest, struct Text *value) {
+  int byteIndex;
+  int totalOfWords = digest->infoType == InfoType_SHA256 ? 8
+                     : digest->infoType == InfoType_SHA1 ? 5
+                                                         : 4;
+  size_t totalOfPaddingBytes;
+  uint64_t hash;
+  uint64_t totalOfBits = digest->totalOfBytes * 8;
+  unsigned char *byte = digest->block;
+  unsigned char padding[72] = {0x80};
+  if (digest->infoType != InfoType_XXH64) {
+    totalOfPaddingBytes =
+        (digest->blockLength < 56 ? 56 : 120) - digest->blockLength;
+    for (byteIndex = 0; byteIndex < 8; byteIndex++) {
+      padding[totalOfPaddingBytes + byteIndex] =
+          totalOfBits >> (digest->infoType == InfoType_MD5
+                              ? byteIndex * 8
+                              : 56 - byteIndex * 8);
+    }
+    updateDigest(digest, padding, totalOfPaddingBytes + 8);
+    for (byteIndex = 0; byteIndex < totalOfWords * 4; byteIndex++) {
+      formatText(value, "%02x",
+                 digest->state[byteIndex / 4] >>
+                         (digest->infoType == InfoType_MD5
+                              ? byteIndex % 4 * 8
+                              : 24 - byteIndex % 4 * 8) &
+                     0xff);
+    }
+    return;
+  }
+  if (digest->blockLength >= 32) {
+    hashXXH64Stripe(digest->lanes, digest->block);
+    byte += 32;
+  }
+  hash = digest->totalOfBytes >= 32
+             ? rotateLeft64(digest->lanes[0], 1) +
+                   rotateLeft64(digest->lanes[1], 7) +
+                   rotateLeft64(digest->lanes[2], 12) +
+                   rotateLeft64(digest->lanes[3], 18)
+             : xxh64Primes_g[4];
+  for (byteIndex = 0; digest->totalOfBytes >= 32 && byteIndex < 4;
+       byteIndex++) {
+    hash ^= rotateLeft64(digest->lanes[byteIndex] * xxh64Primes_g[1], 31) *
+            xxh64Primes_g[0];
+    hash = hash * xxh64Primes_g[0] + xxh64Primes_g[3];
+  }
+  hash += digest->totalOfBytes;
+  for (; byte + 8 <= digest->block + digest->blockLength; byte += 8) {
+    hash ^= rotateLeft64(decodeInteger(byte, 8, 0) * xxh64Primes_g[1], 31) *
+            xxh64Primes_g[0];
+    hash = rotateLeft64(hash, 27) * xxh64Primes_g[0] + xxh64Primes_g[3];
+  }
+  if (byte + 4 <= digest->block + digest->blockLength) {
+    hash ^= decodeInteger(byte, 4, 0) * xxh64Primes_g[0];
+    hash = rotateLeft64(hash, 23) * xxh64Primes_g[1] + xxh64Primes_g[2];
+    byte += 4;
+  }
+  for (; byte < digest->block + digest->blockLength; byte++) {
+    hash ^= *byte * xxh64Primes_g[4];
+    hash = rotateLeft64(hash, 11) * xxh64Primes_g[0];
+  }
+  hash ^= hash >> 33;
+  hash *= xxh64Primes_g[1];
+  hash ^= hash >> 29;
+  hash *= xxh64Primes_g[2];
+  hash ^= hash >> 32;
+  formatText(value, "%016llx", (unsigned long long)hash);
+}
+
+static void formatAttributeNames(struct Text *text, struct Text *names,
+                                 int isSplit) {
+  char *name;
+  formatText(text, "");
+  for (name = names->characters; name < names->characters + names->length;
+       name += strlen(name) + 1) {
+    if (name != names->characters && isSplit) {
+      formatText(text, "%c", terminator_g);
+    } else if (name != names->characters) {
+      formatText(text, ", ");
+    }
+    formatText(text, "%s", name);
+  }
+}
+
+static void formatDate(struct Text *text, char *format,
+                       struct tm *calendarDate) {
+  char *buffer = NULL;
+  size_t length = 0;
+  size_t size;
+  for (size = 64; *format && !length && size <= 65536; size *= 2) {
+    buffer = reallocate(buffer, size);
+    length = strftime(buffer, size, format, calendarDate);
+  }
+  formatText(text, "%s", length ? buffer : "");
+  free(buffer);
+}
+
+static void formatEscapedPath(struct Text *text, char *path) {
+  char *character;
+  for (character = path; *character; character++) {
+    if (*character == '\\') {
+      formatText(text, "\\\\");
+    } else if (*character == '\n') {
+      formatText(text, "\\n");
+    } else {
+      formatText(text, "%c", *character);
+    }
+  }
+}
+
+static void formatInfos(struct Entry *entry, struct Text *line,
+                        char *separator) {
+  char *prefix = "";
+  int infoType;
+  int isSplitting = *separator == '\t' && !entry->depth &&
+                    infoTypes_g >> InfoType_Type ==
+                        1ULL << (InfoType_Attributes - InfoType_Type);
+  struct Text value = {NULL, 0, 0};
+  for (infoType = InfoType_Type; infoTypes_g >> infoType; infoType++) {
+    if (!isRevealing(infoType)) {
       continue;
     }
-    entryName = allocate(strlen(entry->d_name) + 1);
-    entryNames[entryIndexI] = strcpy(entryName, entry->d_name);
-    entryIndexI++;
+    value.length = 0;
+    if (revealInfo(entry->path, &entry->metadata, infoType, &value)) {
+      printFailure(entry->path, infoType);
+      if (outputMode_g != OutputMode_JSON) {
+        formatText(line, "%s?", prefix);
+      }
+    } else if (outputMode_g == OutputMode_JSON) {
+      printValue(entry->path, infoType, &value);
+    } else if (infoType != InfoType_Attributes) {
+      formatText(line, "%s%s", prefix, value.characters);
+    } else if (value.length || !isSplitting) {
+      formatText(line, "%s", prefix);
+      formatAttributeNames(line, &value, isSplitting);
+    }
+    prefix = separator;
+  }
+  free(value.characters);
+}
+
+static void formatPath(struct Text *text, char *path) {
+  char *character;
+  char *safeCharacters = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
+                         "0123456789%+,-./:=@_";
+  if (quotingMode_g == QuotingMode_None || outputMode_g == OutputMode_JSON ||
+      (quotingMode_g == QuotingMode_Shell && *path &&
+       !path[strspn(path, safeCharacters)])) {
+    formatText(text, "%s", path);
+    return;
+  }
+  formatText(text, quotingMode_g == QuotingMode_Shell ? "'" : "\"");
+  for (character = path; *character; character++) {
+    if (quotingMode_g == QuotingMode_Shell && *character == '\'') {
+      formatText(text, "'\\''");
+    } else if (quotingMode_g == QuotingMode_Shell) {
+      formatText(text, "%c", *character);
+    } else if (*character == '"' || *character == '\\') {
+      formatText(text, "\\%c", *character);
+    } else if (*character == '\n') {
+      formatText(text, "\\n");
+    } else if (*character == '\t') {
+      formatText(text, "\\t");
+    } else if ((unsigned char)*character < 32 || *character == 127) {
+      formatText(text, "\\%03o", (unsigned char)*character);
+    } else {
+      formatText(text, "%c", *character);
+    }
+  }
+  formatText(text, quotingMode_g == QuotingMode_Shell ? "'" : "\"");
+}
+
+static void formatText(struct Text *text, char *format, ...) {
+  va_list arguments;
+  va_start(arguments, format);
+  appendText(text, format, arguments);
+  va_end(arguments);
+}
+
+static void formatTreePrefix(struct Text *line, struct Entry *directory,
+                             char **connectors) {
+  if (directory->parent) {
+    formatTreePrefix(line, directory->parent, connectors);
+    formatText(line, "%s", connectors[directory->isLast]);
+  }
+}
+
+static void hashBlock(struct Digest *digest, unsigned char *block) {
+  if (digest->infoType == InfoType_SHA256) {
+    hashSHA256Block(digest->state, block);
+  } else if (digest->infoType == InfoType_SHA1) {
+    hashSHA1Block(digest->state, block);
+  } else if (digest->infoType == InfoType_MD5) {
+    hashMD5Block(digest->state, block);
+  } else {
+    hashXXH64Stripe(digest->lanes, block);
+    hashXXH64Stripe(digest->lanes, block + 32);
+  }
+}
+
+static void hashMD5Block(uint32_t *state, unsigned char *block) {
+  int shifts[] = {7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21};
+  int wordIndex;
+  uint32_t a = state[0];
+  uint32_t b = state[1];
+  uint32_t c = state[2];
+  uint32_t d = state[3];
+  uint32_t mix;
+  uint32_t words[16];
+  for (wordIndex = 0; wordIndex < 16; wordIndex++) {
+    words[wordIndex] = decodeInteger(block + wordIndex * 4, 4, 0);
+  }
+  for (wordIndex = 0; wordIndex < 64; wordIndex++) {
+    mix = wordIndex < 16   ? (b & c) | (~b & d)
+          : wordIndex < 32 ? (d & b) | (~d & c)
+          : wordIndex < 48 ? b ^ c ^ d
+                           : c ^ (b | ~d);
+    mix += a + md5Constants_g[wordIndex] +
+           words[wordIndex < 16   ? wordIndex
+                 : wordIndex < 32 ? (5 * wordIndex + 1) % 16
+                 : wordIndex < 48 ? (3 * wordIndex + 5) % 16
+                                  : 7 * wordIndex % 16];
+    a = d;
+    d = c;
+    c = b;
+    b += rotateLeft(mix, shifts[wordIndex / 16 * 4 + wordIndex % 4]);
+  }
+  state[0] += a;
+  state[1] += b;
+  state[2] += c;
+  state[3] += d;
+}
+
+static void hashSHA1Block(uint32_t *state, unsigned char *block) {
+  int wordIndex;
+  uint32_t a = state[0];
+  uint32_t b = state[1];
+  uint32_t c = state[2];
+  uint32_t d = state[3];
+  uint32_t e = state[4];
+  uint32_t mix;
+  uint32_t words[80];
+  for (wordIndex = 0; wordIndex < 80; wordIndex++) {
+    words[wordIndex] =
+        wordIndex < 16
+            ? decodeInteger(block + wordIndex * 4, 4, 1)
+            : rotateLeft(words[wordIndex - 3] ^ words[wordIndex - 8] ^
+                             words[wordIndex - 14] ^ words[wordIndex - 16],
+                         1);
+  }
+  for (wordIndex = 0; wordIndex < 80; wordIndex++) {
+    mix = rotateLeft(a, 5) + e + words[wordIndex] +
+          (wordIndex < 20   ? ((b & c) | (~b & d)) + 0x5a827999
+           : wordIndex < 40 ? (b ^ c ^ d) + 0x6ed9eba1
+           : wordIndex < 60 ? ((b & c) | (b & d) | (c & d)) + 0x8f1bbcdc
+                            : (b ^ c ^ d) + 0xca62c1d6);
+    e = d;
+    d = c;
+    c = rotateLeft(b, 30);
+    b = a;
+    a = mix;
+  }
+  state[0] += a;
+  state[1] += b;
+  state[2] += c;
+  state[3] += d;
+  state[4] += e;
+}
+
+static void hashSHA256Block(uint32_t *state, unsigned char *block) {
+  int wordIndex;
+  uint32_t a = state[0];
+  uint32_t b = state[1];
+  uint32_t c = state[2];
+  uint32_t d = state[3];
+  uint32_t e = state[4];
+  uint32_t f = state[5];
+  uint32_t g = state[6];
+  uint32_t h = state[7];
+  uint32_t mix;
+  uint32_t otherMix;
+  uint32_t words[64];
+  for (wordIndex = 0; wordIndex < 64; wordIndex++) {
+    words[wordIndex] =
+        wordIndex < 16
+            ? decodeInteger(block + wordIndex * 4, 4, 1)
+            : words[wordIndex - 16] + words[wordIndex - 7] +
+                  (rotateLeft(words[wordIndex - 15], 25) ^
+                   rotateLeft(words[wordIndex - 15], 14) ^
+                   words[wordIndex - 15] >> 3) +
+                  (rotateLeft(words[wordIndex - 2], 15) ^
+                   rotateLeft(words[wordIndex - 2], 13) ^
+                   words[wordIndex - 2] >> 10);
+  }
+  for (wordIndex = 0; wordIndex < 64; wordIndex++) {
+    mix = h + (rotateLeft(e, 26) ^ rotateLeft(e, 21) ^ rotateLeft(e, 7)) +
+          ((e & f) ^ (~e & g)) + sha256Constants_g[wordIndex] +
+          words[wordIndex];
+    otherMix = (rotateLeft(a, 30) ^ rotateLeft(a, 19) ^ rotateLeft(a, 10)) +
+               ((a & b) ^ (a & c) ^ (b & c));
+    h = g;
+    g = f;
+    f = e;
+    e = d + mix;
+    d = c;
+    c = b;
+    b = a;
+    a = mix + otherMix;
+  }
+  state[0] += a;
+  state[1] += b;
+  state[2] += c;
+  state[3] += d;
+  state[4] += e;
+  state[5] += f;
+  state[6] += g;
+  state[7] += h;
+}
+
+static void hashXXH64Stripe(uint64_t *lanes, unsigned char *stripe) {
+  int laneIndex;
+  for (laneIndex = 0; laneIndex < 4; laneIndex++) {
+    lanes[laneIndex] = rotateLeft64(
+        lanes[laneIndex] + decodeInteger(stripe + laneIndex * 8, 8, 0) *
+                               xxh64Primes_g[1],
+        31) * xxh64Primes_g[0];
+  }
+}
+
+static int isBinary(unsigned char *bytes, size_t totalOfBytes) {
+  size_t byteIndex;
+  for (byteIndex = 0; byteIndex < totalOfBytes; byteIndex++) {
+    if (!bytes[byteIndex] || bytes[byteIndex] == 127 ||
+        (bytes[byteIndex] < ' ' && !strchr("\b\t\n\v\f\r", bytes[byteIndex]))) {
+      return 1;
+    }
   }
-  qsort(entryNames, entryIndexI, sizeof(NULL), sortAlphabetically);
-  for (entryIndexII = 0; entryIndexII < entryIndexI; entryIndexII++) {
-    printf("%s\n", entryNames[entryIndexII]);
-    free(entryNames[entryIndexII]);
+  return !isUTF8(bytes, totalOfBytes);
+}
+
+static int isIgnored(char *path, struct stat *metadata) {
+  char *name = strrchr(path, '/') + 1;
+  int isIgnoredPath = 0;
+  size_t ruleIndex;
+  struct IgnoreRule *rule;
+  for (ruleIndex = 0; ruleIndex < totalOfIgnoreRules_g; ruleIndex++) {
+    rule = ignoreRules_g + ruleIndex;
+    if ((!rule->isDirectoryOnly || S_ISDIR(metadata->st_mode)) &&
+        !(rule->isAnchored
+              ? fnmatch(rule->pattern, path + rule->pathLength, FNM_PATHNAME)
+              : fnmatch(rule->pattern, name, 0))) {
+      isIgnoredPath = !rule->isNegated;
+    }
   }
-  free(entryNames);
-close:
-  closedir(stream);
+  return isIgnoredPath;
 }
 
-static void revealFile(char *path) {
+static int isMatching(struct Filter *filter, char *name) {
+  return filter->isRegex ? !regexec(&filter->expression, name, 0, NULL, 0)
+                         : !fnmatch(filter->pattern, name, 0);
+}
+
+static int isNumeric(int infoType) {
+  return infoType == InfoType_Size || infoType == InfoType_UID ||
+         infoType == InfoType_GID || infoType == InfoType_Inode ||
+         infoType == InfoType_HardLinks || infoType == InfoType_Blocks ||
+         infoType == InfoType_AllocatedSize ||
+         infoType == InfoType_TotalSize ||
+         infoType == InfoType_TotalAllocatedSize ||
+         (infoType >= InfoType_ModifiedDate &&
+          infoType <= InfoType_BirthDate && dateFormat_g == DateFormat_Epoch);
+}
+
+static int isPruned(struct Child *child, char *path) {
+  size_t filterIndex;
+  if (isHidingDotEntries_g && *child->name == '.') {
+    return 1;
+  }
+  for (filterIndex = 0; filterIndex < totalOfFilters_g; filterIndex++) {
+    if (filters_g[filterIndex].isExcluding &&
+        isMatching(filters_g + filterIndex, child->name)) {
+      return 1;
+    }
+  }
+  return isRespectingIgnoreFiles_g && isIgnored(path, &child->metadata);
+}
+
+static int isRevealing(int infoType) {
+  return infoTypes_g >> infoType & 1;
+}
+
+static int isSelected(struct Child *child) {
+  int isIncluded = 1;
+  size_t filterIndex;
+  mode_t mode = child->metadata.st_mode;
+  if ((typeFilter_g == TypeFilter_Directories && !S_ISDIR(mode)) ||
+      (typeFilter_g == TypeFilter_RegularFiles && !S_ISREG(mode)) ||
+      (typeFilter_g == TypeFilter_Executables &&
+       (!S_ISREG(mode) || !(mode & (S_IXUSR | S_IXGRP | S_IXOTH))))) {
+    return 0;
+  }
+  for (filterIndex = 0; filterIndex < totalOfFilters_g; filterIndex++) {
+    if (!filters_g[filterIndex].isExcluding) {
+      if (isMatching(filters_g + filterIndex, child->name)) {
+        return 1;
+      }
+      isIncluded = 0;
+    }
+  }
+  return isIncluded;
+}
+
+static int isUTF8(unsigned char *bytes, size_t totalOfBytes) {
+  size_t byteIndex;
+  size_t totalOfContinuations;
+  for (byteIndex = 0; byteIndex < totalOfBytes; byteIndex++) {
+    totalOfContinuations = bytes[byteIndex] < 0x80   ? 0
+                           : bytes[byteIndex] < 0xc2 ? 4
+                           : bytes[byteIndex] < 0xe0 ? 1
+                           : bytes[byteIndex] < 0xf0 ? 2
+                           : bytes[byteIndex] < 0xf5 ? 3
+                                                     : 4;
+    if (totalOfContinuations > 3) {
+      return 0;
+    }
+    for (; totalOfContinuations && ++byteIndex < totalOfBytes;
+         totalOfContinuations--) {
+      if ((bytes[byteIndex] & 0xc0) != 0x80) {
+        return 0;
+      }
+    }
+  }
+  return 1;
+}
+
+static void loadIgnoreFile(int directoryDescriptor, char *directoryPath,
+                           char *name, size_t pathLength) {
+  FILE *file;
+  char *line = NULL;
+  char *pattern;
+  int descriptor = openat(directoryDescriptor, name, O_RDONLY);
+  size_t size = 0;
+  ssize_t length;
+  struct IgnoreRule rule;
+  if (descriptor < 0) {
+    if (errno != ENOENT) {
+      fail(errno, "can't open ignore file \"%s%s\"", directoryPath, name);
+    }
+    return;
+  } else if (!(file = fdopen(descriptor, "r"))) {
+    close(descriptor);
+    fail(errno, "can't open ignore file \"%s%s\"", directoryPath, name);
+    return;
+  }
+  while ((length = getline(&line, &size, file)) >= 0) {
+    for (; length && strchr("\n\r ", line[length - 1]); length--) {
+      line[length - 1] = 0;
+    }
+    if (!*line || *line == '#') {
+      continue;
+    }
+    pattern = line;
+    if ((rule.isNegated = *pattern == '!')) {
+      pattern++;
+    }
+    if (*pattern == '\\') {
+      pattern++;
+    }
+    length = strlen(pattern);
+    if ((rule.isDirectoryOnly = length && pattern[length - 1] == '/')) {
+      pattern[length - 1] = 0;
+    }
+    if (!strncmp(pattern, "**/", 3)) {
+      pattern += 3;
+    }
+    if ((rule.isAnchored = strchr(pattern, '/') != NULL) && *pattern == '/') {
+      pattern++;
+    }
+    if (!*pattern) {
+      continue;
+    }
+    rule.pattern = strcpy(allocate(strlen(pattern) + 1), pattern);
+    rule.pathLength = pathLength;
+    ignoreRules_g = reallocate(ignoreRules_g, sizeof(struct IgnoreRule) *
+                                                  (totalOfIgnoreRules_g + 1));
+    ignoreRules_g[totalOfIgnoreRules_g++] = rule;
+  }
+  free(line);
+  fclose(file);
+}
+
+static long parseNumber(char *string, long minimum) {
+  char *end;
+  long number = strtol(string, &end, 10);
+  if (end == string || *end || number < minimum) {
+    die("invalid number \"%s\".\n", string);
+  }
+  return number;
+}
+
+static void parseRange(char *string, int contentsRange) {
+  char *separator = strchr(string, ':');
+  long last;
+  long minimum = contentsRange == ContentsRange_Lines;
+  if (!separator) {
+    die("invalid range \"%s\".\n", string);
+  }
+  *separator = 0;
+  rangeStart_g = parseNumber(string, minimum);
+  last = parseNumber(separator + 1,
+                     contentsRange == ContentsRange_Lines ? rangeStart_g : 0);
+  *separator = ':';
+  rangeLength_g =
+      contentsRange == ContentsRange_Lines ? last - rangeStart_g + 1 : last;
+  contentsRange_g = contentsRange;
+  selectInfoType(InfoType_Contents);
+}
+
+static void printBase64(unsigned char *bytes, size_t totalOfBytes) {
+  char digits[] =
+      "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+  unsigned long group = (unsigned long)bytes[0] << 16 |
+                        (totalOfBytes > 1 ? bytes[1] << 8 : 0) |
+                        (totalOfBytes > 2 ? bytes[2] : 0);
+  putchar(digits[group >> 18 & 63]);
+  putchar(digits[group >> 12 & 63]);
+  putchar(totalOfBytes > 1 ? digits[group >> 6 & 63] : '=');
+  putchar(totalOfBytes > 2 ? digits[group & 63] : '=');
+}
+
+static size_t printEscaped(unsigned char *bytes, size_t totalOfBytes,
+                           int isEnd) {
+  size_t byteIndex;
+  size_t totalOfSequenceBytes;
+  unsigned char byte;
+  for (byteIndex = 0; byteIndex < totalOfBytes; byteIndex++) {
+    byte = bytes[byteIndex];
+    totalOfSequenceBytes = byte < 0xc2   ? 1
+                           : byte < 0xe0 ? 2
+                           : byte < 0xf0 ? 3
+                           : byte < 0xf5 ? 4
+                                         : 1;
+    if (totalOfSequenceBytes > totalOfBytes - byteIndex && !isEnd &&
+        isUTF8(bytes + byteIndex, totalOfBytes - byteIndex)) {
+      return totalOfBytes - byteIndex;
+    } else if (totalOfSequenceBytes > 1 &&
+               totalOfSequenceBytes <= totalOfBytes - byteIndex &&
+               isUTF8(bytes + byteIndex, totalOfSequenceBytes)) {
+      fwrite(bytes + byteIndex, 1, totalOfSequenceBytes, stdout);
+      byteIndex += totalOfSequenceBytes - 1;
+    } else if ((byte >= ' ' && byte < 127 &&
+                (byte != '\\' || contentsMode_g == ContentsMode_Caret)) ||
+               byte == '\n' || byte == '\t') {
+      putchar(byte);
+    } else if (contentsMode_g == ContentsMode_Caret && byte >= 128) {
+      byte -= 128;
+      printf(byte < ' ' || byte == 127 ? "M-^%c" : "M-%c",
+             byte < ' ' || byte == 127 ? byte ^ 64 : byte);
+    } else if (contentsMode_g == ContentsMode_Caret) {
+      printf("^%c", byte ^ 64);
+    } else if (byte == '\\') {
+      printf("\\\\");
+    } else {
+      printf("\\x%02x", byte);
+    }
+  }
+  return 0;
+}
+
+static void printFailure(char *path, int infoType) {
+  if (outputMode_g == OutputMode_JSON) {
+    printf("{\"path\":");
+    printJSONString(path);
+    printf(",\"info\":\"%s\",\"value\":null,\"error\":",
+           infoTypeNames_g[infoType]);
+    printJSONString(error_g.characters);
+    printf("}%c", terminator_g);
+  }
+  if (isStrict_g) {
+    exit(1);
+  }
+}
+
+static void printFailures(char *path) {
+  int infoType;
+  for (infoType = 0; infoTypes_g >> infoType; infoType++) {
+    if (isRevealing(infoType)) {
+      printFailure(path, infoType);
+    }
+  }
+}
+
+static void printHexdump(unsigned char *bytes, size_t totalOfBytes,
+                         unsigned long long offset) {
+  size_t byteIndex;
+  size_t rowIndex;
+  for (rowIndex = 0; rowIndex < totalOfBytes; rowIndex += 16) {
+    printf("%08llx ", offset + rowIndex);
+    for (byteIndex = rowIndex; byteIndex < rowIndex + 16; byteIndex++) {
+      printf(byteIndex % 8 ? "" : " ");
+      if (byteIndex < totalOfBytes) {
+        printf("%02x ", bytes[byteIndex]);
+      } else {
+        printf("   ");
+      }
+    }
+    printf(" |");
+    for (byteIndex = rowIndex; byteIndex < rowIndex + 16 &&
+                               byteIndex < totalOfBytes;
+         byteIndex++) {
+      putchar(isprint(bytes[byteIndex]) ? bytes[byteIndex] : '.');
+    }
+    printf("|\n");
+  }
+}
+
+static void printJSONString(char *string) {
+  int isValid = isUTF8((unsigned char *)string, strlen(string) + 1);
+  putchar('"');
+  for (; *string; string++) {
+    if (*string == '"' || *string == '\\') {
+      printf("\\%c", *string);
+    } else if (*string == '\n') {
+      printf("\\n");
+    } else if (*string == '\t') {
+      printf("\\t");
+    } else if ((unsigned char)*string < 32 || *string == 127 ||
+               (!isValid && (unsigned char)*string >= 0x80)) {
+      printf("\\u%04x", (unsigned char)*string);
+    } else {
+      putchar(*string);
+    }
+  }
+  putchar('"');
+}
+
+static void printPlaceholders(void) {
+  char *prefix = "";
+  int infoType;
+  for (infoType = InfoType_Type; infoTypes_g >> infoType; infoType++) {
+    if (isRevealing(infoType)) {
+      printf("%s?", prefix);
+      prefix = "\t";
+    }
+  }
+  if (*prefix) {
+    putchar(terminator_g);
+  }
+}
+
+static void printRows(size_t totalOfColumns, char *alignments) {
+  int widths[8] = {0};
+  size_t cellIndex;
+  for (cellIndex = 0; cellIndex < totalOfCells_g; cellIndex++) {
+    if ((int)strlen(cells_g[cellIndex]) > widths[cellIndex % totalOfColumns]) {
+      widths[cellIndex % totalOfColumns] = strlen(cells_g[cellIndex]);
+    }
+  }
+  for (cellIndex = 0; cellIndex < totalOfCells_g; cellIndex++) {
+    if (cellIndex % totalOfColumns == totalOfColumns - 1) {
+      printf("%s%c", cells_g[cellIndex], terminator_g);
+    } else {
+      printf(alignments[cellIndex % totalOfColumns] == 'r' ? "%*s " : "%-*s ",
+             widths[cellIndex % totalOfColumns], cells_g[cellIndex]);
+    }
+    free(cells_g[cellIndex]);
+  }
+  free(cells_g);
+  cells_g = NULL;
+  totalOfCells_g = 0;
+}
+
+static void printUsages(struct Entry *directory) {
+  size_t usageIndex;
+  struct Text value = {NULL, 0, 0};
+  qsort(usages_g, totalOfUsages_g, sizeof(struct Usage), sortUsagesBySize);
+  usages_g = reallocate(usages_g, sizeof(struct Usage) * (totalOfUsages_g + 1));
+  usages_g[totalOfUsages_g].name =
+      strcpy(allocate(strlen(directory->name) + 1), directory->name);
+  usages_g[totalOfUsages_g].size =
+      totalSize_g + (unsigned long long)directory->metadata.st_size;
+  usages_g[totalOfUsages_g++].allocatedSize =
+      totalAllocatedSize_g +
+      (unsigned long long)directory->metadata.st_blocks * 512;
+  cells_g = reallocate(cells_g, sizeof(NULL) * totalOfUsages_g * 3);
+  for (usageIndex = 0; usageIndex < totalOfUsages_g; usageIndex++) {
+    value.length = 0;
+    revealHumanSize(usages_g[usageIndex].size, &value);
+    cells_g[totalOfCells_g++] =
+        strcpy(allocate(value.length + 1), value.characters);
+    value.length = 0;
+    revealHumanSize(usages_g[usageIndex].allocatedSize, &value);
+    cells_g[totalOfCells_g++] =
+        strcpy(allocate(value.length + 1), value.characters);
+    value.length = 0;
+    formatPath(&value, usages_g[usageIndex].name);
+    cells_g[totalOfCells_g++] =
+        strcpy(allocate(value.length + 1), value.characters);
+    free(usages_g[usageIndex].name);
+  }
+  printRows(3, "rrl");
+  free(usages_g);
+  usages_g = NULL;
+  totalOfUsages_g = 0;
+  free(value.characters);
+}
+
+static void printValue(char *path, int infoType, struct Text *value) {
+  char *name;
+  if (outputMode_g == OutputMode_Plain) {
+    printf("%s%c", value->characters, terminator_g);
+    return;
+  }
+  beginRecord(path, infoType);
+  if (infoType == InfoType_Attributes) {
+    putchar('[');
+    for (name = value->characters; name < value->characters + value->length;
+         name += strlen(name) + 1) {
+      if (name != value->characters) {
+        putchar(',');
+      }
+      printJSONString(name);
+    }
+    putchar(']');
+  } else if (isNumeric(infoType)) {
+    printf("%s", value->characters);
+  } else {
+    printJSONString(value->characters);
+  }
+  endRecord();
+}
+
+static ssize_t readAttribute(char *path, int infoType, char *buffer,
+                             size_t size) {
+  if (infoType == InfoType_Attributes) {
+    return isFollowingSymlinks_g ? listxattr(path, buffer, size)
+                                 : llistxattr(path, buffer, size);
+  }
+  return isFollowingSymlinks_g ? getxattr(path, attributeName_g, buffer, size)
+                               : lgetxattr(path, attributeName_g, buffer, size);
+}
+
+static int readChild(int descriptor, char *path, int isStating,
+                     int isRecursive, void (*visit)(struct Entry *entry),
+                     struct Child *child) {
+  if (!strcmp(child->name, ".") || !strcmp(child->name, "..")) {
+    return 0;
+  }
+  child->error =
+      isStating && fstatat(descriptor, child->name, &child->metadata,
+                           isFollowingSymlinks_g ? 0 : AT_SYMLINK_NOFOLLOW)
+          ? errno
+          : 0;
+  if (child->error == ENOENT &&
+      (!isFollowingSymlinks_g || fstatat(descriptor, child->name,
+                                         &child->metadata,
+                                         AT_SYMLINK_NOFOLLOW))) {
+    return 0;
+  } else if (!isStating || child->error) {
+    memset(&child->metadata, 0, sizeof(struct stat));
+  }
+  child->isShown =
+      child->error || isSelected(child) ||
+      (visit == revealTreeNode && S_ISDIR(child->metadata.st_mode));
+  return !isPruned(child, path) &&
+         (child->isShown || (isRecursive && S_ISDIR(child->metadata.st_mode)));
+}
+
+static size_t readRange(FILE *stream, unsigned char *bytes, size_t size,
+                        long *remainingBytes, long *remainingLines) {
+  size_t byteIndex;
+  size_t totalOfBytes;
+  if (!*remainingLines) {
+    return 0;
+  } else if (*remainingBytes >= 0 && (size_t)*remainingBytes < size) {
+    size = *remainingBytes;
+  }
+  totalOfBytes = fread(bytes, 1, size, stream);
+  if (*remainingBytes >= 0) {
+    *remainingBytes -= totalOfBytes;
+  }
+  for (byteIndex = 0; *remainingLines > 0 && byteIndex < totalOfBytes;
+       byteIndex++) {
+    if (bytes[byteIndex] == '\n' && !--*remainingLines) {
+      return byteIndex + 1;
+    }
+  }
+  return totalOfBytes;
+}
+
+static void *reallocate(void *allocation, size_t bytes) {
+  if (!(allocation = realloc(allocation, bytes))) {
+    die("can't allocate memory.\n");
+  }
+  return allocation;
+}
+
+static void reveal(char *path) {
+  int descriptor;
+  struct Entry entry;
+  entry.path = path;
+  entry.name = path;
+  entry.depth = 0;
+  entry.isLast = 1;
+  entry.parent = NULL;
+  if (isFollowingSymlinks_g ? stat(path, &entry.metadata)
+                            : lstat(path, &entry.metadata)) {
+    fail(errno, "can't stat \"%s\"", path);
+    printFailures(path);
+    if (outputMode_g == OutputMode_Plain) {
+      printPlaceholders();
+    }
+    return;
+  } else if (!S_ISDIR(entry.metadata.st_mode)) {
+    revealEntry(&entry);
+    return;
+  } else if (directoryMode_g != DirectoryMode_List &&
+             outputMode_g == OutputMode_Plain &&
+             isRevealing(InfoType_Contents)) {
+    if (revealDirectory(&entry)) {
+      printFailure(path, InfoType_Contents);
+    }
+    return;
+  } else if (!isRecursive_g) {
+    revealEntry(&entry);
+    return;
+  }
+  if (outputMode_g == OutputMode_Format ||
+      outputMode_g == OutputMode_Manifest || infoTypes_g >> InfoType_Type) {
+    if ((descriptor = open(path, O_RDONLY | O_DIRECTORY)) >= 0) {
+      walkDirectory(descriptor, &entry, 1, revealEntry);
+    } else {
+      fail(errno, "can't open directory \"%s\"", path);
+      printFailures(path);
+    }
+  }
+  if (outputMode_g != OutputMode_Format &&
+      outputMode_g != OutputMode_Manifest && isRevealing(InfoType_Contents) &&
+      revealDirectory(&entry)) {
+    printFailure(path, InfoType_Contents);
+  }
+}
+
+static int revealAttribute(char *path, int infoType, struct Text *value) {
+  char *buffer = NULL;
+  char *name;
+  ssize_t byteIndex;
+  ssize_t length;
+  if (infoType == InfoType_Attribute && !attributeName_g) {
+    return fail(0, "can't reveal attribute of \"%s\" as no name was set",
+                path);
+  }
+  while ((length = readAttribute(path, infoType, NULL, 0)) >= 0) {
+    buffer = reallocate(buffer, length + 1);
+    if ((length = readAttribute(path, infoType, buffer, length)) >= 0 ||
+        errno != ERANGE) {
+      break;
+    }
+  }
+  if (length < 0 && infoType == InfoType_Attributes) {
+    free(buffer);
+    return fail(errno, "can't list attributes of \"%s\"", path);
+  } else if (length < 0) {
+    free(buffer);
+    return fail(errno, "can't reveal attribute \"%s\" of \"%s\"",
+                attributeName_g, path);
+  }
+  formatText(value, "");
+  if (infoType == InfoType_Attributes) {
+    for (name = buffer; name < buffer + length; name += strlen(name) + 1) {
+      formatText(value, "%s%c", name, '\0');
+    }
+  } else if (length && !buffer[length - 1] &&
+             !isBinary((unsigned char *)buffer, length - 1)) {
+    formatText(value, "%s", buffer);
+  } else if (!isBinary((unsigned char *)buffer, length)) {
+    formatText(value, "%.*s", (int)length, buffer);
+  } else {
+    formatText(value, "0x");
+    for (byteIndex = 0; byteIndex < length; byteIndex++) {
+      formatText(value, "%02x", (unsigned char)buffer[byteIndex]);
+    }
+  }
+  free(buffer);
+  return 0;
+}
+
+static int revealBirthDate(char *path, struct Text *value) {
+  struct statx metadata;
+  struct timespec date;
+  if (statx(AT_FDCWD, path, isFollowingSymlinks_g ? 0 : AT_SYMLINK_NOFOLLOW,
+            STATX_BTIME, &metadata)) {
+    return fail(errno, "can't stat \"%s\"", path);
+  } else if (!(metadata.stx_mask & STATX_BTIME)) {
+    return fail(ENOTSUP, "can't reveal birth date of \"%s\"", path);
+  }
+  date.tv_sec = metadata.stx_btime.tv_sec;
+  date.tv_nsec = metadata.stx_btime.tv_nsec;
+  revealDate(&date, value);
+  return 0;
+}
+
+static int revealContents(struct Entry *entry) {
+  struct Text target = {NULL, 0, 0};
+  struct Text value = {NULL, 0, 0};
+  if (S_ISREG(entry->metadata.st_mode)) {
+    return revealFile(entry->path);
+  } else if (S_ISDIR(entry->metadata.st_mode)) {
+    return revealDirectory(entry);
+  } else if (!S_ISLNK(entry->metadata.st_mode)) {
+    return fail(0, "can't reveal contents of \"%s\"", entry->path);
+  }
+  if (revealSymlink(entry->path, &entry->metadata, &target)) {
+    free(target.characters);
+    return -1;
+  }
+  formatPath(&value, target.characters);
+  printValue(entry->name, InfoType_Contents, &value);
+  free(target.characters);
+  free(value.characters);
+  return 0;
+}
+
+static void revealDate(struct timespec *date, struct Text *value) {
+  char *character;
+  char *units[] = {"year", "month", "week", "day", "hour", "minute", "second"};
+  long long elapsed = time(NULL) - date->tv_sec;
+  long long seconds[] = {31536000, 2592000, 604800, 86400, 3600, 60, 1};
+  long long total;
+  int unitIndex;
+  struct Text pattern = {NULL, 0, 0};
+  struct tm *calendarDate =
+      isUsingUTC_g ? gmtime(&date->tv_sec) : localtime(&date->tv_sec);
+  if (dateFormat_g == DateFormat_Epoch && date->tv_sec < 0 && date->tv_nsec &&
+      isRevealingNanoseconds_g) {
+    formatText(value, "-%lld.%09ld", -(long long)date->tv_sec - 1,
+               1000000000 - date->tv_nsec);
+  } else if (dateFormat_g == DateFormat_Epoch) {
+    formatText(value, "%lld", (long long)date->tv_sec);
+    if (isRevealingNanoseconds_g) {
+      formatText(value, ".%09ld", date->tv_nsec);
+    }
+  } else if (dateFormat_g == DateFormat_Relative) {
+    for (unitIndex = 0; unitIndex < 7; unitIndex++) {
+      if ((total = (elapsed < 0 ? -elapsed : elapsed) / seconds[unitIndex])) {
+        formatText(value, elapsed < 0 ? "in %lld %s%s" : "%lld %s%s ago", total,
+                   units[unitIndex], total > 1 ? "s" : "");
+        return;
+      }
+    }
+    formatText(value, "now");
+  } else if (dateFormat_g == DateFormat_Custom) {
+    for (character = datePattern_g; *character; character++) {
+      if (*character == '%' && character[1] == 'N') {
+        formatText(&pattern, "%09ld", date->tv_nsec);
+        character++;
+      } else if (*character == '%' && character[1]) {
+        formatText(&pattern, "%%%c", *++character);
+      } else {
+        formatText(&pattern, "%c", *character);
+      }
+    }
+    formatDate(value, pattern.length ? pattern.characters : "", calendarDate);
+    free(pattern.characters);
+  } else if (dateFormat_g == DateFormat_ISO ||
+             outputMode_g == OutputMode_JSON) {
+    formatDate(value, "%Y-%m-%dT%T", calendarDate);
+    if (isRevealingNanoseconds_g) {
+      formatText(value, ".%09ld", date->tv_nsec);
+    }
+    if (isUsingUTC_g) {
+      formatText(value, "Z");
+      return;
+    }
+    formatDate(&pattern, "%z", calendarDate);
+    formatText(value, "%.3s:%s", pattern.characters, pattern.characters + 3);
+    free(pattern.characters);
+  } else {
+    formatDate(value, "%a %b %d %T", calendarDate);
+    if (isRevealingNanoseconds_g) {
+      formatText(value, ".%09ld", date->tv_nsec);
+    }
+    formatDate(value, " %Z %Y", calendarDate);
+  }
+}
+
+static int revealDigest(char *path, struct stat *metadata, int infoType,
+                        struct Text *value) {
+  int descriptor;
+  int status = 0;
+  ssize_t length;
+  struct Digest digest;
+  unsigned char *bytes;
+  if (!S_ISREG(metadata->st_mode)) {
+    return fail(0, "can't hash \"%s\" as it isn't a regular file", path);
+  } else if ((descriptor = open(path, O_RDONLY)) < 0) {
+    return fail(errno, "can't open file \"%s\"", path);
+  }
+  bytes = allocate(131072);
+  beginDigest(&digest, infoType);
+  for (;;) {
+    if ((length = read(descriptor, bytes, 131072)) > 0) {
+      updateDigest(&digest, bytes, length);
+    } else if (!length || errno != EINTR) {
+      break;
+    }
+  }
+  if (length < 0) {
+    status = fail(errno, "can't read file \"%s\"", path);
+  } else {
+    finishDigest(&digest, value);
+  }
+  free(bytes);
+  close(descriptor);
+  return status;
+}
+
+static int revealDirectory(struct Entry *directory) {
+  int descriptor = open(directory->path, O_RDONLY | O_DIRECTORY);
+  int isTree = directoryMode_g == DirectoryMode_Tree ||
+               directoryMode_g == DirectoryMode_ASCIITree;
+  struct Text name = {NULL, 0, 0};
+  if (descriptor < 0) {
+    return fail(errno, "can't open directory \"%s\"", directory->path);
+  }
+  beginRecord(directory->name, InfoType_Contents);
+  hasListedEntry_g = 0;
+  if (outputMode_g == OutputMode_JSON) {
+    putchar('[');
+    walkDirectory(descriptor, directory, isRecursive_g || isTree, revealName);
+    putchar(']');
+  } else if (isTree) {
+    formatPath(&name, directory->name);
+    printf("%s%c", name.characters, terminator_g);
+    free(name.characters);
+    walkDirectory(descriptor, directory, 1, revealTreeNode);
+  } else if (directoryMode_g == DirectoryMode_Long) {
+    walkDirectory(descriptor, directory, isRecursive_g, revealRow);
+    printRows(8, "llrllrll");
+  } else if (directoryMode_g == DirectoryMode_Usage) {
+    clearTotals();
+    walkDirectory(descriptor, directory, 0, revealUsage);
+    printUsages(directory);
+  } else {
+    walkDirectory(descriptor, directory, isRecursive_g, revealName);
+  }
+  endRecord();
+  return 0;
+}
+
+static void revealEntry(struct Entry *entry) {
+  struct Text line = {NULL, 0, 0};
+  if (outputMode_g == OutputMode_Format) {
+    revealFormat(entry);
+    return;
+  } else if (outputMode_g == OutputMode_Manifest) {
+    revealManifestEntry(entry);
+    return;
+  }
+  formatInfos(entry, &line, "\t");
+  if (line.characters && entry->depth) {
+    formatText(&line, "\t");
+    formatPath(&line, entry->name);
+  }
+  if (line.characters) {
+    fwrite(line.characters, 1, line.length, stdout);
+    putchar(terminator_g);
+  }
+  if (!entry->depth && isRevealing(InfoType_Contents) &&
+      revealContents(entry)) {
+    printFailure(entry->name, InfoType_Contents);
+  }
+  free(line.characters);
+}
+
+static int revealFile(char *path) {
   FILE *stream = fopen(path, "r");
-  int character;
+  long remainingBytes =
+      contentsRange_g == ContentsRange_Bytes ? rangeLength_g : -1;
+  long remainingLines = contentsRange_g == ContentsRange_All ||
+                                contentsRange_g == ContentsRange_Bytes
+                            ? -1
+                            : rangeLength_g;
+  int status;
+  off_t offset;
+  size_t byteIndex;
+  size_t totalOfBytes;
+  size_t totalOfPendingBytes = 0;
+  unsigned char bytes[3072];
   if (!stream) {
-    die("can't open file \"%s\".\n", path);
+    return fail(errno, "can't open file \"%s\"", path);
+  } else if ((offset = findRangeStart(stream)) < 0 ||
+             fseeko(stream, offset, SEEK_SET)) {
+    fclose(stream);
+    return fail(errno, "can't read file \"%s\"", path);
   }
-  for (; (character = fgetc(stream)) != EOF; putchar(character))
-    ;
+  if (outputMode_g == OutputMode_Plain &&
+      contentsMode_g <= ContentsMode_ForcedRaw && remainingLines < 0 &&
+      !isatty(STDOUT_FILENO)) {
+    status = copyContents(fileno(stream), offset, remainingBytes, path);
+    fclose(stream);
+    return status;
+  }
+  totalOfBytes = readRange(stream, bytes, sizeof(bytes), &remainingBytes,
+                           &remainingLines);
+  if (outputMode_g == OutputMode_Plain && contentsMode_g == ContentsMode_Raw &&
+      isatty(STDOUT_FILENO) && isBinary(bytes, totalOfBytes)) {
+    fclose(stream);
+    return fail(0,
+                "can't reveal contents of \"%s\" as it is binary and the "
+                "output is a terminal",
+                path);
+  }
+  beginRecord(path, InfoType_Contents);
+  if (outputMode_g == OutputMode_JSON) {
+    putchar('"');
+  }
+  while (totalOfBytes) {
+    if (outputMode_g == OutputMode_JSON) {
+      for (byteIndex = 0; byteIndex < totalOfBytes; byteIndex += 3) {
+        printBase64(bytes + byteIndex, totalOfBytes - byteIndex < 3
+                                           ? totalOfBytes - byteIndex
+                                           : 3);
+      }
+    } else if (contentsMode_g == ContentsMode_Hexdump) {
+      printHexdump(bytes, totalOfBytes, offset);
+    } else if (contentsMode_g >= ContentsMode_Escaped) {
+      totalOfPendingBytes =
+          printEscaped(bytes, totalOfBytes, totalOfBytes < sizeof(bytes));
+      memmove(bytes, bytes + totalOfBytes - totalOfPendingBytes,
+              totalOfPendingBytes);
+    } else {
+      fwrite(bytes, 1, totalOfBytes, stdout);
+    }
+    offset += totalOfBytes - totalOfPendingBytes;
+    totalOfBytes = totalOfPendingBytes +
+                   readRange(stream, bytes + totalOfPendingBytes,
+                             sizeof(bytes) - totalOfPendingBytes,
+                             &remainingBytes, &remainingLines);
+  }
+  if (outputMode_g == OutputMode_JSON) {
+    putchar('"');
+  } else if (contentsMode_g == ContentsMode_Hexdump && offset) {
+    printf("%08llx\n", (unsigned long long)offset);
+  }
+  endRecord();
   fclose(stream);
+  return 0;
+}
+
+static int revealFileFormat(char *path, struct stat *metadata, int infoType,
+                            struct Text *value) {
+  char *formats[] = {"directory",    "symlink", "character device",
+                     "block device", "fifo",    "socket"};
+  char *mimeType;
+  char *mimeTypes[] = {"inode/directory",  "inode/symlink",
+                       "inode/chardevice", "inode/blockdevice",
+                       "inode/fifo",       "inode/socket"};
+  int type = S_ISDIR(metadata->st_mode)    ? 0
+             : S_ISLNK(metadata->st_mode)  ? 1
+             : S_ISCHR(metadata->st_mode)  ? 2
+             : S_ISBLK(metadata->st_mode)  ? 3
+             : S_ISFIFO(metadata->st_mode) ? 4
+                                           : 5;
+  size_t totalOfBytes;
+  struct Text format = {NULL, 0, 0};
+  unsigned char bytes[4097];
+  FILE *stream;
+  if (!S_ISREG(metadata->st_mode)) {
+    formatText(value, "%s",
+               infoType == InfoType_MIMEType ? mimeTypes[type] : formats[type]);
+    return 0;
+  } else if (!(stream = fopen(path, "r"))) {
+    return fail(errno, "can't open file \"%s\"", path);
+  }
+  totalOfBytes = fread(bytes, 1, sizeof(bytes) - 1, stream);
+  if (ferror(stream)) {
+    fclose(stream);
+    return fail(errno, "can't read file \"%s\"", path);
+  }
+  fclose(stream);
+  bytes[totalOfBytes] = 0;
+  mimeType = detectFormat(bytes, totalOfBytes, &format);
+  formatText(value, "%s",
+             infoType == InfoType_MIMEType ? mimeType : format.characters);
+  free(format.characters);
+  return 0;
 }
 
-static void revealGroup(char *path, struct stat *metadata) {
+static void revealFormat(struct Entry *entry) {
+  char *character;
+  char *directive;
+  char *directives = " tsHpouUgGmacbihdrBAMKTSDERFICJQXLV";
+  char padding;
+  int isLeftAligned;
+  int width;
+  struct Text line = {NULL, 0, 0};
+  struct Text names = {NULL, 0, 0};
+  struct Text value = {NULL, 0, 0};
+  for (character = format_g; *character; character++) {
+    if (*character == '\\' && character[1]) {
+      character++;
+      formatText(&line, "%c",
+                 *character == 'n'   ? '\n'
+                 : *character == 't' ? '\t'
+                 : *character == '0' ? '\0'
+                                     : *character);
+      continue;
+    } else if (*character != '%') {
+      formatText(&line, "%c", *character);
+      continue;
+    }
+    isLeftAligned = 0;
+    padding = ' ';
+    for (character++; *character == '-' || *character == '0'; character++) {
+      if (*character == '-') {
+        isLeftAligned = 1;
+      } else {
+        padding = '0';
+      }
+    }
+    for (width = 0; isdigit((unsigned char)*character); character++) {
+      width = width * 10 + *character - '0';
+    }
+    value.length = 0;
+    if (*character == '%') {
+      formatText(&value, "%%");
+    } else if (*character == 'n') {
+      formatPath(&value, entry->name);
+    } else if (*character && (directive = strchr(directives + 1, *character))) {
+      if (revealInfo(entry->path, &entry->metadata, directive - directives,
+                     &value)) {
+        printFailure(entry->name, directive - directives);
+        value.length = 0;
+        formatText(&value, "?");
+      } else if (directive - directives == InfoType_Attributes) {
+        names.length = 0;
+        formatAttributeNames(&names, &value, 0);
+        value.length = 0;
+        formatText(&value, "%s", names.characters);
+      }
+    } else {
+      die("invalid format directive in \"%s\".\n", format_g);
+    }
+    for (; !isLeftAligned && padding == '0' && width > (int)value.length;
+         width--) {
+      formatText(&line, "0");
+    }
+    formatText(&line, isLeftAligned ? "%-*s" : "%*s", width, value.characters);
+  }
+  fwrite(line.characters, 1, line.length, stdout);
+  free(line.characters);
+  free(names.characters);
+  free(value.characters);
+}
+
+static int revealGroup(char *path, struct stat *metadata, struct Text *value) {
   char buffer[255];
+  int error;
   struct group *result;
   struct group group;
-  if (getgrgid_r(metadata->st_gid, &group, buffer, sizeof(buffer), &result) ||
+  if ((error = getgrgid_r(metadata->st_gid, &group, buffer, sizeof(buffer),
+                          &result)) ||
       !result) {
-    die("can't find group that owns \"%s\".\n", path);
+    return fail(error, "can't find group that owns \"%s\"", path);
   }
-  printf("%s\n", group.gr_name);
+  formatText(value, "%s", group.gr_name);
+  return 0;
 }
 
-static void revealHumanSize(struct stat *metadata) {
-  char prefixes[] = {'G', 'M', 'k'};
-  float multipliers[] = {1e9, 1e6, 1e3};
-  float size;
-  int multiplierIndex;
-  for (multiplierIndex = 0; multiplierIndex < 3; multiplierIndex++) {
-    if ((size = metadata->st_size / multipliers[multiplierIndex]) >= 1) {
-      printf("%.1f%cB\n", size, prefixes[multiplierIndex]);
-      return;
+static void revealHumanSize(unsigned long long size, struct Text *value) {
+  char *units[][7] = {{"B", "kB", "MB", "GB", "TB", "PB", "EB"},
+                      {"B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"}};
+  unsigned long long base = isUsingBinaryUnits_g ? 1024 : 1000;
+  unsigned long long unit = 1;
+  unsigned long long integer;
+  unsigned long long remainder;
+  struct Text fraction = {NULL, 0, 0};
+  int unitIndex = 0;
+  long digitIndex;
+  while (unitIndex < 6 && size / unit >= base) {
+    unit *= base;
+    unitIndex++;
+  }
+  if (!unitIndex) {
+    formatText(value, "%lluB", size);
+    return;
+  }
+  for (;;) {
+    integer = size / unit;
+    remainder = size % unit;
+    fraction.length = 0;
+    for (digitIndex = 0; digitIndex < humanSizePrecision_g; digitIndex++) {
+      remainder *= 10;
+      formatText(&fraction, "%c", (int)('0' + remainder / unit));
+      remainder %= unit;
     }
+    if (remainder >= unit - remainder) {
+      for (digitIndex = (long)fraction.length - 1;
+           digitIndex >= 0 && fraction.characters[digitIndex] == '9';
+           digitIndex--) {
+        fraction.characters[digitIndex] = '0';
+      }
+      if (digitIndex < 0) {
+        integer++;
+      } else {
+        fraction.characters[digitIndex]++;
+      }
+    }
+    if (integer < base || unitIndex == 6) {
+      break;
+    }
+    unit *= base;
+    unitIndex++;
+  }
+  formatText(value, "%llu%s%s%s", integer, fraction.length ? "." : "",
+             fraction.length ? fraction.characters : "",
+             units[isUsingBinaryUnits_g][unitIndex]);
+  free(fraction.characters);
+}
+
+static int revealInfo(char *path, struct stat *metadata, int infoType,
+                      struct Text *value) {
+  if (infoType == InfoType_Type) {
+    revealType(metadata, value);
+  } else if (infoType == InfoType_Size) {
+    formatText(value, "%ld", metadata->st_size);
+  } else if (infoType == InfoType_HumanSize) {
+    revealHumanSize(metadata->st_size, value);
+  } else if (infoType == InfoType_Permissions) {
+    revealPermissions(metadata, value);
+  } else if (infoType == InfoType_OctalPermissions) {
+    formatText(value, "%04o", metadata->st_mode & 07777);
+  } else if (infoType == InfoType_Mode) {
+    revealMode(metadata, value);
+  } else if (infoType == InfoType_User) {
+    return revealUser(path, metadata, value);
+  } else if (infoType == InfoType_UID) {
+    formatText(value, "%u", metadata->st_uid);
+  } else if (infoType == InfoType_Group) {
+    return revealGroup(path, metadata, value);
+  } else if (infoType == InfoType_GID) {
+    formatText(value, "%u", metadata->st_gid);
+  } else if (infoType == InfoType_ModifiedDate) {
+    revealDate(&metadata->st_mtim, value);
+  } else if (infoType == InfoType_AccessDate) {
+    revealDate(&metadata->st_atim, value);
+  } else if (infoType == InfoType_StatusChangeDate) {
+    revealDate(&metadata->st_ctim, value);
+  } else if (infoType == InfoType_BirthDate) {
+    return revealBirthDate(path, value);
+  } else if (infoType == InfoType_Inode) {
+    formatText(value, "%llu", (unsigned long long)metadata->st_ino);
+  } else if (infoType == InfoType_HardLinks) {
+    formatText(value, "%lu", (unsigned long)metadata->st_nlink);
+  } else if (infoType == InfoType_Device) {
+    formatText(value, "%u:%u", major(metadata->st_dev),
+               minor(metadata->st_dev));
+  } else if (infoType == InfoType_DeviceNumbers) {
+    if (!S_ISCHR(metadata->st_mode) && !S_ISBLK(metadata->st_mode)) {
+      return fail(0, "can't reveal device numbers of \"%s\" as it isn't a "
+                     "character or block device",
+                  path);
+    }
+    formatText(value, "%u:%u", major(metadata->st_rdev),
+               minor(metadata->st_rdev));
+  } else if (infoType == InfoType_Blocks) {
+    formatText(value, "%lld", (long long)metadata->st_blocks);
+  } else if (infoType == InfoType_AllocatedSize) {
+    formatText(value, "%lld", (long long)metadata->st_blocks * 512);
+  } else if (infoType == InfoType_HumanAllocatedSize) {
+    revealHumanSize((unsigned long long)metadata->st_blocks * 512, value);
+  } else if (infoType >= InfoType_TotalSize &&
+             infoType <= InfoType_HumanTotalAllocatedSize) {
+    revealTotalSize(path, metadata, infoType, value);
+  } else if (infoType == InfoType_Resolution) {
+    return revealResolution(path, value);
+  } else if (infoType == InfoType_Format || infoType == InfoType_MIMEType) {
+    return revealFileFormat(path, metadata, infoType, value);
+  } else if (infoType >= InfoType_SHA256 && infoType <= InfoType_XXH64) {
+    return revealDigest(path, metadata, infoType, value);
+  } else if (infoType == InfoType_Attributes ||
+             infoType == InfoType_Attribute) {
+    return revealAttribute(path, infoType, value);
+  }
+  return 0;
+}
+
+static void revealManifestEntry(struct Entry *entry) {
+  int infoType;
+  int isEscaped = terminator_g && strpbrk(entry->path, "\\\n");
+  struct Text line = {NULL, 0, 0};
+  struct Text value = {NULL, 0, 0};
+  for (infoType = InfoType_SHA256;
+       infoType <= InfoType_XXH64 && !isRevealing(infoType); infoType++)
+    ;
+  if (infoType > InfoType_XXH64) {
+    infoType = InfoType_SHA256;
+  }
+  if (entry->depth && !S_ISREG(entry->metadata.st_mode)) {
+    return;
+  } else if (revealDigest(entry->path, &entry->metadata, infoType, &value)) {
+    printFailure(entry->name, infoType);
+    free(value.characters);
+    return;
+  }
+  if (isEscaped) {
+    formatText(&line, "\\%s  ", value.characters);
+    formatEscapedPath(&line, entry->path);
+  } else {
+    formatText(&line, "%s  %s", value.characters, entry->path);
+  }
+  printf("%s%c", line.characters, terminator_g);
+  free(line.characters);
+  free(value.characters);
+}
+
+static void revealMode(struct stat *metadata, struct Text *value) {
+  formatText(value, "%c",
+             S_ISREG(metadata->st_mode)    ? '-'
+             : S_ISDIR(metadata->st_mode)  ? 'd'
+             : S_ISLNK(metadata->st_mode)  ? 'l'
+             : S_ISCHR(metadata->st_mode)  ? 'c'
+             : S_ISBLK(metadata->st_mode)  ? 'b'
+             : S_ISFIFO(metadata->st_mode) ? 'p'
+                                           : 's');
+  revealPermissions(metadata, value);
+}
+
+static void revealName(struct Entry *entry) {
+  struct Text name = {NULL, 0, 0};
+  if (outputMode_g == OutputMode_JSON) {
+    if (hasListedEntry_g) {
+      putchar(',');
+    }
+    printJSONString(entry->name);
+  } else {
+    formatPath(&name, entry->name);
+    printf("%s%c", name.characters, terminator_g);
+    free(name.characters);
   }
-  printf("%ldB\n", metadata->st_size);
+  hasListedEntry_g = 1;
 }
 
-static void revealModifiedDate(struct stat *metadata) {
-  char buffer[29];
-  strftime(buffer, sizeof(buffer), "%a %b %d %T %Z %Y",
-           localtime(&metadata->st_mtime));
-  printf("%s\n", buffer);
+static void revealPaths(char *listPath) {
+  char *path = NULL;
+  size_t size = 0;
+  ssize_t length;
+  FILE *stream = strcmp(listPath, "-") ? fopen(listPath, "r") : stdin;
+  if (!stream) {
+    fail(errno, "can't open path list \"%s\"", listPath);
+    if (isStrict_g) {
+      exit(1);
+    }
+    return;
+  }
+  while ((length = getdelim(&path, &size, inputDelimiter_g, stream)) > 0) {
+    if (path[length - 1] == inputDelimiter_g) {
+      path[--length] = 0;
+    }
+    if (length) {
+      reveal(path);
+      fflush(stdout);
+    }
+  }
+  if (ferror(stream)) {
+    fail(errno, "can't read path list \"%s\"", listPath);
+    if (isStrict_g) {
+      exit(1);
+    }
+  }
+  if (stream != stdin) {
+    fclose(stream);
+  }
+  free(path);
 }
 
-static void revealPermissions(struct stat *metadata) {
+static void revealPermissions(struct stat *metadata, struct Text *value) {
   char characters[] = {'r', 'w', 'x'};
+  char specialCharacters[] = {'s', 's', 't'};
   int flags[] = {S_IRUSR, S_IWUSR, S_IXUSR, S_IRGRP, S_IWGRP,
                  S_IXGRP, S_IROTH, S_IWOTH, S_IXOTH};
   int flagIndex;
+  int isSet;
+  int specialFlags[] = {S_ISUID, S_ISGID, S_ISVTX};
   for (flagIndex = 0; flagIndex < 9; flagIndex++) {
-    putchar(metadata->st_mode & flags[flagIndex]
-                ? characters[flagIndex < 3 ? flagIndex : (flagIndex - 3) % 3]
-                : '-');
+    isSet = metadata->st_mode & flags[flagIndex];
+    if (flagIndex % 3 == 2 && metadata->st_mode & specialFlags[flagIndex / 3]) {
+      formatText(value, "%c",
+                 isSet ? specialCharacters[flagIndex / 3]
+                       : toupper(specialCharacters[flagIndex / 3]));
+    } else {
+      formatText(value, "%c", isSet ? characters[flagIndex % 3] : '-');
+    }
+  }
+}
+
+static int revealResolution(char *path, struct Text *value) {
+  char *canonicalPath;
+  char *slash;
+  int status = 0;
+  size_t inodeIndex;
+  size_t totalOfInodes = 0;
+  struct Inode *inodes = NULL;
+  struct Text hop = {NULL, 0, 0};
+  struct Text target = {NULL, 0, 0};
+  struct stat metadata;
+  formatText(&hop, "%s", path);
+  formatPath(value, path);
+  for (;;) {
+    if (lstat(hop.characters, &metadata)) {
+      if (totalOfInodes && (errno == ENOENT || errno == ENOTDIR)) {
+        formatText(value, " (dangling)");
+      } else {
+        status = fail(errno, "can't stat \"%s\"", hop.characters);
+      }
+      break;
+    } else if (!S_ISLNK(metadata.st_mode)) {
+      if (!(canonicalPath = realpath(hop.characters, NULL))) {
+        status = fail(errno, "can't resolve \"%s\"", hop.characters);
+        break;
+      } else if (strcmp(canonicalPath, hop.characters)) {
+        formatText(value, " -> ");
+        formatPath(value, canonicalPath);
+      }
+      free(canonicalPath);
+      break;
+    }
+    for (inodeIndex = 0; inodeIndex < totalOfInodes &&
+                         (inodes[inodeIndex].device != metadata.st_dev ||
+                          inodes[inodeIndex].inode != metadata.st_ino);
+         inodeIndex++)
+      ;
+    if (inodeIndex < totalOfInodes) {
+      formatText(value, " (loop)");
+      break;
+    }
+    inodes = reallocate(inodes, sizeof(struct Inode) * (totalOfInodes + 1));
+    inodes[totalOfInodes].device = metadata.st_dev;
+    inodes[totalOfInodes++].inode = metadata.st_ino;
+    target.length = 0;
+    if ((status = revealSymlink(hop.characters, &metadata, &target))) {
+      break;
+    }
+    slash = strrchr(hop.characters, '/');
+    hop.length = *target.characters != '/' && slash
+                     ? (size_t)(slash - hop.characters) + 1
+                     : 0;
+    formatText(&hop, "%s", target.characters);
+    formatText(value, " -> ");
+    formatPath(value, hop.characters);
+  }
+  free(inodes);
+  free(hop.characters);
+  free(target.characters);
+  return status;
+}
+
+static void revealRow(struct Entry *entry) {
+  int infoTypes[] = {InfoType_Type,  InfoType_Permissions, InfoType_HardLinks,
+                     InfoType_User,  InfoType_Group,       InfoType_HumanSize,
+                     InfoType_ModifiedDate};
+  int cellIndex;
+  struct Text target = {NULL, 0, 0};
+  struct Text value = {NULL, 0, 0};
+  cells_g = reallocate(cells_g, sizeof(NULL) * (totalOfCells_g + 8));
+  for (cellIndex = 0; cellIndex < 7; cellIndex++) {
+    value.length = 0;
+    if (revealInfo(entry->path, &entry->metadata, infoTypes[cellIndex],
+                   &value)) {
+      printFailure(entry->name, infoTypes[cellIndex]);
+      value.length = 0;
+      formatText(&value, "?");
+    }
+    cells_g[totalOfCells_g++] =
+        strcpy(allocate(value.length + 1), value.characters);
+  }
+  value.length = 0;
+  formatPath(&value, entry->name);
+  if (S_ISLNK(entry->metadata.st_mode)) {
+    formatText(&value, " -> ");
+    if (revealSymlink(entry->path, &entry->metadata, &target)) {
+      formatText(&value, "?");
+    } else {
+      formatPath(&value, target.characters);
+    }
+  }
+  cells_g[totalOfCells_g++] =
+      strcpy(allocate(value.length + 1), value.characters);
+  free(target.characters);
+  free(value.characters);
+}
+
+static int revealSymlink(char *path, struct stat *metadata,
+                         struct Text *value) {
+  char *buffer = NULL;
+  size_t size = metadata->st_size > 0 ? (size_t)metadata->st_size + 1 : 64;
+  ssize_t length;
+  for (;; size *= 2) {
+    buffer = reallocate(buffer, size);
+    if ((length = readlink(path, buffer, size)) < 0) {
+      free(buffer);
+      return fail(errno, "can't read symlink \"%s\"", path);
+    } else if ((size_t)length < size) {
+      break;
+    }
+  }
+  buffer[length] = 0;
+  formatText(value, "%s", buffer);
+  free(buffer);
+  return 0;
+}
+
+static void revealTotalSize(char *path, struct stat *metadata, int infoType,
+                            struct Text *value) {
+  struct Entry directory;
+  directory.path = path;
+  directory.name = path;
+  directory.depth = 0;
+  directory.isLast = 1;
+  directory.metadata = *metadata;
+  directory.parent = NULL;
+  clearTotals();
+  sumDirectory(&directory);
+  if (infoType == InfoType_TotalSize) {
+    formatText(value, "%llu", totalSize_g);
+  } else if (infoType == InfoType_HumanTotalSize) {
+    revealHumanSize(totalSize_g, value);
+  } else if (infoType == InfoType_TotalAllocatedSize) {
+    formatText(value, "%llu", totalAllocatedSize_g);
+  } else {
+    revealHumanSize(totalAllocatedSize_g, value);
+  }
+}
+
+static void revealTreeNode(struct Entry *entry) {
+  char *asciiConnectors[] = {"|   ", "    ", "|-- ", "`-- "};
+  char *boxConnectors[] = {"│   ", "    ", "├── ", "└── "};
+  char **connectors = directoryMode_g == DirectoryMode_Tree ? boxConnectors
+                                                            : asciiConnectors;
+  char *name = strrchr(entry->name, '/');
+  struct Text annotations = {NULL, 0, 0};
+  struct Text line = {NULL, 0, 0};
+  formatTreePrefix(&line, entry->parent, connectors);
+  formatText(&line, "%s", connectors[2 + entry->isLast]);
+  formatInfos(entry, &annotations, " ");
+  if (annotations.characters) {
+    formatText(&line, "[%s]  ", annotations.characters);
   }
-  putchar('\n');
+  formatPath(&line, name ? name + 1 : entry->name);
+  printf("%s%c", line.characters, terminator_g);
+  free(annotations.characters);
+  free(line.characters);
 }
 
-static void revealSymlink(char *path) {
-  char buffer[100];
-  buffer[readlink(path, buffer, sizeof(buffer))] = 0;
-  printf("%s\n", buffer);
+static void revealType(struct stat *metadata, struct Text *value) {
+  formatText(value, "%c",
+             S_ISREG(metadata->st_mode)    ? 'r'
+             : S_ISDIR(metadata->st_mode)  ? 'd'
+             : S_ISLNK(metadata->st_mode)  ? 'l'
+             : S_ISCHR(metadata->st_mode)  ? 'c'
+             : S_ISBLK(metadata->st_mode)  ? 'b'
+             : S_ISFIFO(metadata->st_mode) ? 'f'
+                                           : 's');
 }
 
-static void revealType(struct stat *metadata) {
-  printf("%c\n", S_ISREG(metadata->st_mode)    ? 'r'
-                 : S_ISDIR(metadata->st_mode)  ? 'd'
-                 : S_ISLNK(metadata->st_mode)  ? 'l'
-                 : S_ISCHR(metadata->st_mode)  ? 'c'
-                 : S_ISBLK(metadata->st_mode)  ? 'b'
-                 : S_ISFIFO(metadata->st_mode) ? 'f'
-                                               : 's');
+static void revealUsage(struct Entry *entry) {
+  unsigned long long size = totalSize_g;
+  unsigned long long allocatedSize = totalAllocatedSize_g;
+  sumDirectory(entry);
+  usages_g = reallocate(usages_g, sizeof(struct Usage) * (totalOfUsages_g + 1));
+  usages_g[totalOfUsages_g].name =
+      strcpy(allocate(strlen(entry->name) + 1), entry->name);
+  usages_g[totalOfUsages_g].size = totalSize_g - size;
+  usages_g[totalOfUsages_g++].allocatedSize =
+      totalAllocatedSize_g - allocatedSize;
 }
 
-static void revealUser(char *path, struct stat *metadata) {
+static int revealUser(char *path, struct stat *metadata, struct Text *value) {
   char buffer[255];
+  int error;
   struct passwd *result;
   struct passwd user;
-  if (getpwuid_r(metadata->st_uid, &user, buffer, sizeof(buffer), &result) ||
+  if ((error = getpwuid_r(metadata->st_uid, &user, buffer, sizeof(buffer),
+                          &result)) ||
       !result) {
-    die("can't find user that owns \"%s\".\n", path);
+    return fail(error, "can't find user that owns \"%s\"", path);
   }
-  printf("%s\n", user.pw_name);
+  formatText(value, "%s", user.pw_name);
+  return 0;
+}
+
+static uint32_t rotateLeft(uint32_t value, int shift) {
+  return value << shift | value >> (32 - shift);
 }
 
-static int sortAlphabetically(const void *stringI, const void *stringII) {
-  return strcmp(*(char **)stringI, *(char **)stringII);
+static uint64_t rotateLeft64(uint64_t value, int shift) {
+  return value << shift | value >> (64 - shift);
+}
+
+static void selectInfoType(int infoType) {
+  if (isResettingInfoTypes_g) {
+    infoTypes_g = 0;
+    isResettingInfoTypes_g = 0;
+  }
+  infoTypes_g |= 1ULL << infoType;
+}
+
+static int sortChildren(const void *childI, const void *childII) {
+  const struct Child *child = childI;
+  const struct Child *otherChild = childII;
+  const struct timespec *date = &child->metadata.st_mtim;
+  const struct timespec *otherDate = &otherChild->metadata.st_mtim;
+  int order = 0;
+  if (sortMode_g == SortMode_Natural) {
+    order = compareNaturally(child->name, otherChild->name);
+  } else if (sortMode_g == SortMode_Locale) {
+    order = strcoll(child->name, otherChild->name);
+  } else if (sortMode_g == SortMode_CaseInsensitive) {
+    order = strcasecmp(child->name, otherChild->name);
+  } else if (sortMode_g == SortMode_Size &&
+             child->metadata.st_size != otherChild->metadata.st_size) {
+    order = child->metadata.st_size < otherChild->metadata.st_size ? 1 : -1;
+  } else if (sortMode_g == SortMode_ModifiedDate &&
+             (date->tv_sec != otherDate->tv_sec ||
+              date->tv_nsec != otherDate->tv_nsec)) {
+    order = date->tv_sec < otherDate->tv_sec ||
+                    (date->tv_sec == otherDate->tv_sec &&
+                     date->tv_nsec < otherDate->tv_nsec)
+                ? 1
+                : -1;
+  } else if (sortMode_g == SortMode_Type) {
+    order = !S_ISDIR(child->metadata.st_mode) -
+            !S_ISDIR(otherChild->metadata.st_mode);
+  } else if (sortMode_g == SortMode_Extension) {
+    order = strcmp(findExtension(child->name), findExtension(otherChild->name));
+  }
+  if (!order) {
+    order = strcmp(child->name, otherChild->name);
+  }
+  return isReversingSort_g ? -order : order;
+}
+
+static int sortUsagesBySize(const void *usageI, const void *usageII) {
+  const struct Usage *usage = usageI;
+  const struct Usage *otherUsage = usageII;
+  if (usage->size != otherUsage->size) {
+    return usage->size < otherUsage->size ? 1 : -1;
+  }
+  return strcmp(usage->name, otherUsage->name);
+}
+
+static void sumDirectory(struct Entry *directory) {
+  long maxDepth = maxDepth_g;
+  sumSize(directory);
+  if (S_ISDIR(directory->metadata.st_mode)) {
+    maxDepth_g = -1;
+    if (walkSubdirectory(directory, 1, sumSize) && isStrict_g) {
+      exit(1);
+    }
+    maxDepth_g = maxDepth;
+  }
+}
+
+static void sumSize(struct Entry *entry) {
+  if (!S_ISDIR(entry->metadata.st_mode) && entry->metadata.st_nlink > 1 &&
+      !countInode(&entry->metadata)) {
+    return;
+  }
+  totalSize_g += entry->metadata.st_size;
+  totalAllocatedSize_g += (unsigned long long)entry->metadata.st_blocks * 512;
+}
+
+static void updateDigest(struct Digest *digest, unsigned char *bytes,
+                         size_t totalOfBytes) {
+  size_t length;
+  digest->totalOfBytes += totalOfBytes;
+  for (; totalOfBytes; bytes += length, totalOfBytes -= length) {
+    if (!digest->blockLength && totalOfBytes >= 64) {
+      hashBlock(digest, bytes);
+      length = 64;
+      continue;
+    }
+    length = 64 - digest->blockLength < totalOfBytes
+                 ? 64 - digest->blockLength
+                 : totalOfBytes;
+    memcpy(digest->block + digest->blockLength, bytes, length);
+    if ((digest->blockLength += length) == 64) {
+      hashBlock(digest, digest->block);
+      digest->blockLength = 0;
+    }
+  }
+}
+
+static void verifyManifest(char *manifestPath) {
+  char *character;
+  char *digest;
+  char *line = NULL;
+  char *path;
+  char *unescapedCharacter;
+  int infoType;
+  size_t digestLength;
+  size_t size = 0;
+  ssize_t length;
+  struct stat metadata;
+  struct Text result = {NULL, 0, 0};
+  struct Text value = {NULL, 0, 0};
+  unsigned long lineNumber = 0;
+  unsigned long totalOfChecks = 0;
+  unsigned long totalOfMismatches = 0;
+  FILE *stream = strcmp(manifestPath, "-") ? fopen(manifestPath, "r") : stdin;
+  if (!stream) {
+    fail(errno, "can't open manifest \"%s\"", manifestPath);
+    if (isStrict_g) {
+      exit(1);
+    }
+    return;
+  }
+  while ((length = getdelim(&line, &size, inputDelimiter_g, stream)) > 0) {
+    lineNumber++;
+    if (line[length - 1] == inputDelimiter_g) {
+      line[--length] = 0;
+    }
+    if (!length) {
+      continue;
+    }
+    digest = line + (*line == '\\');
+    digestLength = strspn(digest, "0123456789abcdefABCDEF");
+    infoType = digestLength == 64   ? InfoType_SHA256
+               : digestLength == 40 ? InfoType_SHA1
+               : digestLength == 32 ? InfoType_MD5
+               : digestLength == 16 ? InfoType_XXH64
+                                    : -1;
+    if (infoType < 0 || digest[digestLength] != ' ' ||
+        (digest[digestLength + 1] != ' ' && digest[digestLength + 1] != '*') ||
+        !digest[digestLength + 2]) {
+      fail(0, "invalid line %lu in manifest \"%s\"", lineNumber, manifestPath);
+      if (isStrict_g) {
+        exit(1);
+      }
+      continue;
+    }
+    digest[digestLength] = 0;
+    path = digest + digestLength + 2;
+    for (character = unescapedCharacter = path; *line == '\\' && *character;
+         character++, unescapedCharacter++) {
+      if (*character == '\\' && character[1] == 'n') {
+        *unescapedCharacter = '\n';
+        character++;
+      } else if (*character == '\\' && character[1] == '\\') {
+        *unescapedCharacter = '\\';
+        character++;
+      } else {
+        *unescapedCharacter = *character;
+      }
+    }
+    if (*line == '\\') {
+      *unescapedCharacter = 0;
+    }
+    value.length = 0;
+    result.length = 0;
+    totalOfChecks++;
+    if (terminator_g && strpbrk(path, "\\\n")) {
+      formatText(&result, "\\");
+      formatEscapedPath(&result, path);
+    } else {
+      formatPath(&result, path);
+    }
+    if (stat(path, &metadata)
+            ? fail(errno, "can't stat \"%s\"", path)
+            : revealDigest(path, &metadata, infoType, &value)) {
+      formatText(&result, ": FAILED");
+      totalOfMismatches++;
+    } else if (strcasecmp(value.characters, digest)) {
+      formatText(&result, ": FAILED");
+      totalOfMismatches++;
+      hasFailed_g = 1;
+    } else {
+      formatText(&result, ": OK");
+    }
+    printf("%s%c", result.characters, terminator_g);
+    if (totalOfMismatches && isStrict_g) {
+      exit(1);
+    }
+  }
+  if (ferror(stream)) {
+    fail(errno, "can't read manifest \"%s\"", manifestPath);
+  } else if (totalOfMismatches) {
+    fail(0, "%lu of %lu checksums in manifest \"%s\" didn't match",
+         totalOfMismatches, totalOfChecks, manifestPath);
+  }
+  if (stream != stdin) {
+    fclose(stream);
+  }
+  free(line);
+  free(result.characters);
+  free(value.characters);
+}
+
+static void walkDirectory(int descriptor, struct Entry *directory,
+                          int isRecursive, void (*visit)(struct Entry *entry)) {
+  char *buffer = NULL;
+  int isEnd;
+  int isStating =
+      isRecursive || visit != revealName ||
+      (sortMode_g >= SortMode_Size && sortMode_g <= SortMode_Type) ||
+      typeFilter_g != TypeFilter_All || isRespectingIgnoreFiles_g;
+  int isStreaming = sortMode_g == SortMode_None;
+  int status;
+  size_t bufferCapacity = 0;
+  size_t bufferLength = 0;
+  size_t childIndex;
+  size_t childrenCapacity = 0;
+  size_t nameLength;
+  size_t offset;
+  size_t pathLength;
+  size_t totalOfChildren = 0;
+  size_t totalOfIgnoreRules = totalOfIgnoreRules_g;
+  ssize_t length;
+  struct Child *children = NULL;
+  struct Entry child;
+  struct Text childName = {NULL, 0, 0};
+  struct Text childPath = {NULL, 0, 0};
+  struct Text pendingName = {NULL, 0, 0};
+  struct dirent64 *record;
+  formatText(&childPath,
+             directory->path[strlen(directory->path) - 1] == '/' ? "%s"
+                                                                 : "%s/",
+             directory->path);
+  formatText(&childName, directory->depth ? "%s/" : "", directory->name);
+  pathLength = childPath.length;
+  nameLength = childName.length;
+  if (isRespectingIgnoreFiles_g) {
+    loadIgnoreFile(descriptor, childPath.characters, ".gitignore", pathLength);
+    loadIgnoreFile(descriptor, childPath.characters, ".ignore", pathLength);
+  }
+  child.depth = directory->depth + 1;
+  child.parent = directory;
+  for (isEnd = 0; !isEnd;) {
+    if (bufferCapacity - bufferLength < 32768) {
+      bufferCapacity = bufferCapacity ? bufferCapacity * 2 : 32768;
+      buffer = reallocate(buffer, bufferCapacity);
+    }
+    if ((length = getdents64(descriptor, buffer + bufferLength,
+                             bufferCapacity - bufferLength)) < 0) {
+      fail(errno, "can't read directory \"%s\"", directory->path);
+      if (isStrict_g) {
+        exit(1);
+      }
+    }
+    isEnd = length <= 0;
+    bufferLength += isEnd ? 0 : length;
+    if (!isStreaming && !isEnd) {
+      continue;
+    }
+    for (offset = 0; offset < bufferLength; offset += record->d_reclen) {
+      record = (struct dirent64 *)(buffer + offset);
+      if (totalOfChildren == childrenCapacity) {
+        childrenCapacity = childrenCapacity ? childrenCapacity * 2 : 64;
+        children =
+            reallocate(children, sizeof(struct Child) * childrenCapacity);
+      }
+      children[totalOfChildren].name = record->d_name;
+      childPath.length = pathLength;
+      formatText(&childPath, "%s", record->d_name);
+      totalOfChildren +=
+          readChild(descriptor, childPath.characters, isStating, isRecursive,
+                    visit, children + totalOfChildren);
+    }
+    bufferLength = 0;
+    if (!isStreaming) {
+      qsort(children, totalOfChildren, sizeof(struct Child), sortChildren);
+    }
+    for (childIndex = 0; childIndex + !isEnd < totalOfChildren; childIndex++) {
+      childPath.length = pathLength;
+      childName.length = nameLength;
+      formatText(&childPath, "%s", children[childIndex].name);
+      formatText(&childName, "%s", children[childIndex].name);
+      child.path = childPath.characters;
+      child.name = childName.characters;
+      child.isLast = isEnd && childIndex == totalOfChildren - 1;
+      child.metadata = children[childIndex].metadata;
+      if (children[childIndex].error) {
+        status = fail(children[childIndex].error, "can't stat \"%s\"",
+                      child.path);
+        if (visit == revealEntry) {
+          printFailures(child.path);
+        }
+      } else {
+        if (children[childIndex].isShown) {
+          visit(&child);
+        }
+        status = isRecursive && S_ISDIR(child.metadata.st_mode) &&
+                         (maxDepth_g < 0 || child.depth < maxDepth_g) &&
+                         (isCrossingFileSystems_g ||
+                          child.metadata.st_dev == directory->metadata.st_dev)
+                     ? walkSubdirectory(&child, isRecursive, visit)
+                     : 0;
+      }
+      if (status && isStrict_g) {
+        exit(1);
+      }
+    }
+    if (!isEnd && totalOfChildren) {
+      children[0] = children[totalOfChildren - 1];
+      if (children[0].name != pendingName.characters) {
+        pendingName.length = 0;
+        formatText(&pendingName, "%s", children[0].name);
+        children[0].name = pendingName.characters;
+      }
+      totalOfChildren = 1;
+    }
+  }
+  for (; totalOfIgnoreRules_g > totalOfIgnoreRules; totalOfIgnoreRules_g--) {
+    free(ignoreRules_g[totalOfIgnoreRules_g - 1].pattern);
+  }
+  free(buffer);
+  free(children);
+  free(childName.characters);
+  free(childPath.characters);
+  free(pendingName.characters);
+  close(descriptor);
+}
+
+static int walkSubdirectory(struct Entry *directory, int isRecursive,
+                            void (*visit)(struct Entry *entry)) {
+  int descriptor;
+  struct Entry *ancestor;
+  for (ancestor = directory->parent; ancestor; ancestor = ancestor->parent) {
+    if (ancestor->metadata.st_dev == directory->metadata.st_dev &&
+        ancestor->metadata.st_ino == directory->metadata.st_ino) {
+      return fail(0, "can't walk directory \"%s\" as it leads to a loop",
+                  directory->path);
+    }
+  }
+  if ((descriptor = open(directory->path, O_RDONLY | O_DIRECTORY)) < 0) {
+    return errno == ENOENT ? 0
+                           : fail(errno, "can't open directory \"%s\"",
+                                  directory->path);
+  }
+  walkDirectory(descriptor, directory, isRecursive, visit);
+  return 0;
 }
 
 int main(int totalOfArguments, char **arguments) {
   int argumentIndex;
+  setlocale(LC_COLLATE, "");
   for (argumentIndex = 1; argumentIndex < totalOfArguments; argumentIndex++) {
-    PARSE_INFO_TYPE_OPTION("c", InfoType_Contents);
+    PARSE_DIRECTORY_MODE_OPTION("c", DirectoryMode_List);
+    PARSE_DIRECTORY_MODE_OPTION("tree", DirectoryMode_Tree);
+    PARSE_DIRECTORY_MODE_OPTION("atree", DirectoryMode_ASCIITree);
+    PARSE_DIRECTORY_MODE_OPTION("l", DirectoryMode_Long);
+    PARSE_DIRECTORY_MODE_OPTION("usage", DirectoryMode_Usage);
+    PARSE_CONTENTS_MODE_OPTION("cr", ContentsMode_Raw);
+    PARSE_CONTENTS_MODE_OPTION("cfr", ContentsMode_ForcedRaw);
+    PARSE_CONTENTS_MODE_OPTION("ch", ContentsMode_Hexdump);
+    PARSE_CONTENTS_MODE_OPTION("ce", ContentsMode_Escaped);
+    PARSE_CONTENTS_MODE_OPTION("cv", ContentsMode_Caret);
+    PARSE_OPTION("ca", selectInfoType(InfoType_Contents);
+                 contentsRange_g = ContentsRange_All; continue);
+    PARSE_VALUE_OPTION("cfl", selectInfoType(InfoType_Contents);
+                       contentsRange_g = ContentsRange_FirstLines;
+                       rangeLength_g =
+                           parseNumber(arguments[argumentIndex], 0));
+    PARSE_VALUE_OPTION("cll", selectInfoType(InfoType_Contents);
+                       contentsRange_g = ContentsRange_LastLines;
+                       rangeLength_g =
+                           parseNumber(arguments[argumentIndex], 0));
+    PARSE_VALUE_OPTION("clr", parseRange(arguments[argumentIndex],
+                                         ContentsRange_Lines));
+    PARSE_VALUE_OPTION("cbr", parseRange(arguments[argumentIndex],
+                                         ContentsRange_Bytes));
     PARSE_INFO_TYPE_OPTION("t", InfoType_Type);
     PARSE_INFO_TYPE_OPTION("s", InfoType_Size);
     PARSE_INFO_TYPE_OPTION("hs", InfoType_HumanSize);
@@ -241,9 +2961,101 @@ int main(int totalOfArguments, char **arguments) {
     PARSE_INFO_TYPE_OPTION("g", InfoType_Group);
     PARSE_INFO_TYPE_OPTION("gi", InfoType_GID);
     PARSE_INFO_TYPE_OPTION("md", InfoType_ModifiedDate);
+    PARSE_INFO_TYPE_OPTION("ad", InfoType_AccessDate);
+    PARSE_INFO_TYPE_OPTION("cd", InfoType_StatusChangeDate);
+    PARSE_INFO_TYPE_OPTION("bd", InfoType_BirthDate);
+    PARSE_INFO_TYPE_OPTION("i", InfoType_Inode);
+    PARSE_INFO_TYPE_OPTION("hl", InfoType_HardLinks);
+    PARSE_INFO_TYPE_OPTION("dv", InfoType_Device);
+    PARSE_INFO_TYPE_OPTION("dvn", InfoType_DeviceNumbers);
+    PARSE_INFO_TYPE_OPTION("b", InfoType_Blocks);
+    PARSE_INFO_TYPE_OPTION("as", InfoType_AllocatedSize);
+    PARSE_INFO_TYPE_OPTION("m", InfoType_Mode);
+    PARSE_INFO_TYPE_OPTION("has", InfoType_HumanAllocatedSize);
+    PARSE_INFO_TYPE_OPTION("ts", InfoType_TotalSize);
+    PARSE_INFO_TYPE_OPTION("hts", InfoType_HumanTotalSize);
+    PARSE_INFO_TYPE_OPTION("tas", InfoType_TotalAllocatedSize);
+    PARSE_INFO_TYPE_OPTION("htas", InfoType_HumanTotalAllocatedSize);
+    PARSE_INFO_TYPE_OPTION("rc", InfoType_Resolution);
+    PARSE_INFO_TYPE_OPTION("fmt", InfoType_Format);
+    PARSE_INFO_TYPE_OPTION("mime", InfoType_MIMEType);
+    PARSE_INFO_TYPE_OPTION("sha256", InfoType_SHA256);
+    PARSE_INFO_TYPE_OPTION("sha1", InfoType_SHA1);
+    PARSE_INFO_TYPE_OPTION("md5", InfoType_MD5);
+    PARSE_INFO_TYPE_OPTION("xxh64", InfoType_XXH64);
+    PARSE_INFO_TYPE_OPTION("attrs", InfoType_Attributes);
+    PARSE_VALUE_OPTION("attr", selectInfoType(InfoType_Attribute);
+                       attributeName_g = arguments[argumentIndex]);
     PARSE_SYMLINK_OPTION("ul", 0);
     PARSE_SYMLINK_OPTION("fl", 1);
-    reveal(arguments[argumentIndex]);
+    PARSE_DATE_FORMAT_OPTION("dd", DateFormat_Default);
+    PARSE_DATE_FORMAT_OPTION("di", DateFormat_ISO);
+    PARSE_DATE_FORMAT_OPTION("de", DateFormat_Epoch);
+    PARSE_DATE_FORMAT_OPTION("dr", DateFormat_Relative);
+    PARSE_VALUE_OPTION("df", dateFormat_g = DateFormat_Custom;
+                       datePattern_g = arguments[argumentIndex]);
+    PARSE_OPTION("dl", isUsingUTC_g = 0; continue);
+    PARSE_OPTION("du", isUsingUTC_g = 1; continue);
+    PARSE_OPTION("ds", isRevealingNanoseconds_g = 0; continue);
+    PARSE_OPTION("dn", isRevealingNanoseconds_g = 1; continue);
+    PARSE_OPTION("e", isStrict_g = 1; continue);
+    PARSE_OPTION("hd", isUsingBinaryUnits_g = 0; continue);
+    PARSE_OPTION("hb", isUsingBinaryUnits_g = 1; continue);
+    PARSE_VALUE_OPTION("hp", humanSizePrecision_g =
+                                 parseNumber(arguments[argumentIndex], 0));
+    PARSE_OPTION("r", isRecursive_g = 1; maxDepth_g = -1; continue);
+    PARSE_VALUE_OPTION("d", isRecursive_g = 1;
+                       maxDepth_g = parseNumber(arguments[argumentIndex], 1));
+    PARSE_OPTION("cf", isCrossingFileSystems_g = 1; continue);
+    PARSE_OPTION("sf", isCrossingFileSystems_g = 0; continue);
+    PARSE_OPTION("ah", isHidingDotEntries_g = 0; continue);
+    PARSE_OPTION("xh", isHidingDotEntries_g = 1; continue);
+    PARSE_FILTER_OPTION("ig", 0, 0);
+    PARSE_FILTER_OPTION("xg", 0, 1);
+    PARSE_FILTER_OPTION("ie", 1, 0);
+    PARSE_FILTER_OPTION("xe", 1, 1);
+    PARSE_TYPE_FILTER_OPTION("ka", TypeFilter_All);
+    PARSE_TYPE_FILTER_OPTION("kd", TypeFilter_Directories);
+    PARSE_TYPE_FILTER_OPTION("kr", TypeFilter_RegularFiles);
+    PARSE_TYPE_FILTER_OPTION("kx", TypeFilter_Executables);
+    PARSE_OPTION("ai", isRespectingIgnoreFiles_g = 0; continue);
+    PARSE_OPTION("xi", isRespectingIgnoreFiles_g = 1; continue);
+    PARSE_SORT_MODE_OPTION("on", SortMode_Name);
+    PARSE_SORT_MODE_OPTION("ov", SortMode_Natural);
+    PARSE_SORT_MODE_OPTION("ol", SortMode_Locale);
+    PARSE_SORT_MODE_OPTION("oi", SortMode_CaseInsensitive);
+    PARSE_SORT_MODE_OPTION("os", SortMode_Size);
+    PARSE_SORT_MODE_OPTION("om", SortMode_ModifiedDate);
+    PARSE_SORT_MODE_OPTION("ot", SortMode_Type);
+    PARSE_SORT_MODE_OPTION("oe", SortMode_Extension);
+    PARSE_SORT_MODE_OPTION("ou", SortMode_None);
+    PARSE_OPTION("of", isReversingSort_g = 0; continue);
+    PARSE_OPTION("or", isReversingSort_g = 1; continue);
+    PARSE_QUOTING_MODE_OPTION("qn", QuotingMode_None);
+    PARSE_QUOTING_MODE_OPTION("qs", QuotingMode_Shell);
+    PARSE_QUOTING_MODE_OPTION("qc", QuotingMode_C);
+    PARSE_OPTION("0", terminator_g = 0; continue);
+    PARSE_OPTION("nl", terminator_g = '\n'; continue);
+    PARSE_OUTPUT_MODE_OPTION("pt", OutputMode_Plain);
+    PARSE_OUTPUT_MODE_OPTION("j", OutputMode_JSON);
+    PARSE_OUTPUT_MODE_OPTION("sum", OutputMode_Manifest);
+    PARSE_VALUE_OPTION("f", format_g = arguments[argumentIndex];
+                       outputMode_g = OutputMode_Format);
+    PARSE_OPTION("0i", inputDelimiter_g = 0; continue);
+    PARSE_OPTION("nli", inputDelimiter_g = '\n'; continue);
+    PARSE_VALUE_OPTION("-files-from", revealPaths(arguments[argumentIndex]);
+                       isResettingInfoTypes_g = 1;
+                       isResettingFilters_g = 1);
+    PARSE_VALUE_OPTION("verify", verifyManifest(arguments[argumentIndex]);
+                       isResettingInfoTypes_g = 1;
+                       isResettingFilters_g = 1);
+    if (!strcmp(arguments[argumentIndex], "-")) {
+      revealPaths("-");
+    } else {
+      reveal(arguments[argumentIndex]);
+    }
+    isResettingInfoTypes_g = 1;
+    isResettingFilters_g = 1;
   }
-  return 0;
+  return hasFailed_g ? 2 : 0;
 }